@@ -27,6 +27,9 @@ pub async fn login(
     State(_state): State<AppState>,
     Json(payload): Json<LoginRequest>,
 ) -> (StatusCode, Json<AuthResponse>) {
+    if payload.email.is_empty() || payload.password.is_empty() {
+        return missing_fields("Email e password sono obbligatorie");
+    }
     // TODO: replace with real authentication logic
     let response = AuthResponse {
         success: true,
@@ -40,6 +43,9 @@ pub async fn register(
     State(_state): State<AppState>,
     Json(payload): Json<RegisterRequest>,
 ) -> (StatusCode, Json<AuthResponse>) {
+    if payload.username.is_empty() || payload.email.is_empty() || payload.password.is_empty() {
+        return missing_fields("Username, email e password sono obbligatori");
+    }
     // TODO: replace with real registration logic
     let response = AuthResponse {
         success: true,
@@ -48,3 +54,8 @@ pub async fn register(
     };
     (StatusCode::CREATED, Json(response))
 }
+
+fn missing_fields(message: &str) -> (StatusCode, Json<AuthResponse>) {
+    let response = AuthResponse { success: false, message: message.to_string(), token: None };
+    (StatusCode::BAD_REQUEST, Json(response))
+}
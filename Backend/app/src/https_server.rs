@@ -1,33 +1,41 @@
 use axum::{
-    extract::State,
-    http::Method,
-    response::Json,
-    routing::{get, post},
+    extract::{Path, State},
+    http::{header, Method, StatusCode},
+    response::{IntoResponse, Json, Response},
+    routing::{delete, get, post},
     Router,
 };
 use rustls::{Certificate, PrivateKey, ServerConfig};
 use rustls_pemfile::{certs, pkcs8_private_keys};
+use serde::Deserialize;
 use serde_json::{json, Value};
-use std::{
-    fs::File,
-    io::{BufReader, Error as IoError, ErrorKind},
-    sync::Arc,
+use std::{fs::File, io::BufReader, sync::Arc};
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::conn::auto::Builder as ConnectionBuilder,
+    service::TowerToHyperService,
 };
-use futures::StreamExt;
 use tokio::net::TcpListener;
 use tokio_rustls::TlsAcceptor;
-use tokio_stream::wrappers::TcpListenerStream;
 use tower::ServiceBuilder;
 use tower_http::cors::{Any, CorsLayer};
 
 use crate::auth;
+use crate::websocket::websocket_routes;
+use crate::models::{
+    notation::{export_notation, import_notation},
+    GamePhase, GameRules, GameState,
+};
+use crate::rooms::GameRegistry;
 
 // ===========================================
 // APPLICATION STATE
 // ===========================================
 
 #[derive(Clone, Default)]
-pub struct AppState;
+pub struct AppState {
+    pub games: GameRegistry,
+}
 
 // ===========================================
 // HTTPS CONFIGURATION
@@ -67,14 +75,159 @@ async fn health_check() -> Json<Value> {
     }))
 }
 
-async fn create_game(State(_state): State<AppState>) -> Json<Value> {
-    let game_id = uuid::Uuid::new_v4().to_string();
+#[derive(Debug, Default, Deserialize)]
+pub struct CreateGameRequest {
+    #[serde(default)]
+    pub rules: GameRules,
+}
 
-    Json(json!({
-        "success": true,
-        "game_id": game_id,
-        "message": "Game created successfully"
-    }))
+async fn create_game(
+    State(state): State<AppState>,
+    payload: Option<Json<CreateGameRequest>>,
+) -> (StatusCode, Json<Value>) {
+    let Json(request) = payload.unwrap_or_default();
+
+    match state.games.create(request.rules.clone()).await {
+        Ok(game_id) => (
+            StatusCode::CREATED,
+            Json(json!({
+                "success": true,
+                "game_id": game_id,
+                "rules": request.rules,
+                "message": "Game created successfully"
+            })),
+        ),
+        Err(err) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "success": false,
+                "code": err.code(),
+                "message": err.to_string(),
+                "details": err.details()
+            })),
+        ),
+    }
+}
+
+async fn delete_game(
+    State(state): State<AppState>,
+    Path(game_id): Path<String>,
+) -> (StatusCode, Json<Value>) {
+    match state.games.remove(&game_id).await {
+        Some(_) => (
+            StatusCode::OK,
+            Json(json!({
+                "success": true,
+                "message": "Game deleted successfully"
+            })),
+        ),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "success": false,
+                "message": "Game not found"
+            })),
+        ),
+    }
+}
+
+//  Registro della partita, verificato rigiocandolo da capo. Durante la partita
+//  seed e previsioni a buste chiuse non ancora rivelate sono oscurati.
+async fn game_log(
+    State(state): State<AppState>,
+    Path(game_id): Path<String>,
+) -> (StatusCode, Json<Value>) {
+    let Some(table) = state.games.get(&game_id).await else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "success": false,
+                "message": "Game not found"
+            })),
+        );
+    };
+    let (log, public_log) = {
+        let table = table.lock().await;
+        (table.game.log.clone(), table.game.public_log())
+    };
+    let verification = GameState::replay(&log).err().map(|err| err.to_json());
+    (
+        StatusCode::OK,
+        Json(json!({
+            "success": true,
+            "game_id": game_id,
+            "verified": verification.is_none(),
+            "verification_error": verification,
+            "log": public_log
+        })),
+    )
+}
+
+//  Partita in notazione testuale, da scaricare e condividere. La notazione
+//  contiene seed e previsioni: e disponibile solo a partita finita.
+async fn game_replay(
+    State(state): State<AppState>,
+    Path(game_id): Path<String>,
+) -> Response {
+    let Some(table) = state.games.get(&game_id).await else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "success": false,
+                "message": "Game not found"
+            })),
+        )
+            .into_response();
+    };
+    let table = table.lock().await;
+    if table.game.phase != GamePhase::Finished {
+        return (
+            StatusCode::CONFLICT,
+            Json(json!({
+                "success": false,
+                "message": "Replay available once the game is finished"
+            })),
+        )
+            .into_response();
+    }
+    let notation = export_notation(&table.game.log);
+    (
+        [
+            (header::CONTENT_TYPE, "text/plain; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"bisca-{}.txt\"", game_id),
+            ),
+        ],
+        notation,
+    )
+        .into_response()
+}
+
+//  Valida una partita in notazione rigiocandola nel motore
+async fn validate_replay(body: String) -> (StatusCode, Json<Value>) {
+    match import_notation(&body) {
+        Ok(game) => (
+            StatusCode::OK,
+            Json(json!({
+                "success": true,
+                "valid": true,
+                "phase": game.phase,
+                "round_number": game.round_number,
+                "standings": (game.phase == GamePhase::Finished).then(|| game.final_standings())
+            })),
+        ),
+        Err(err) => (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(json!({
+                "success": false,
+                "valid": false,
+                "code": err.code(),
+                "message": err.to_string(),
+                "details": err.details()
+            })),
+        ),
+    }
 }
 
 async fn join_game(State(_state): State<AppState>) -> Json<Value> {
@@ -109,9 +262,14 @@ fn build_router(state: AppState) -> Router {
         .route("/login", post(auth::login))
         .route("/register", post(auth::register))
         .route("/games", post(create_game))
+        .route("/games/:game_id", delete(delete_game))
+        .route("/games/:game_id/log", get(game_log))
+        .route("/games/:game_id/replay", get(game_replay))
+        .route("/replays/validate", post(validate_replay))
         .route("/games/:game_id/join", post(join_game))
         .route("/games/:game_id/play-card", post(play_card))
         .route("/games/:game_id/prediction", post(make_prediction))
+        .merge(websocket_routes())
         .with_state(state)
         .layer(ServiceBuilder::new().layer(cors))
 }
@@ -128,40 +286,41 @@ pub async fn run_https_server() -> Result<(), Box<dyn std::error::Error>> {
 
     let state = AppState::default();
     let router = build_router(state);
-    let service = router.into_make_service();
 
     let listener = TcpListener::bind("0.0.0.0:443").await?;
-    let incoming = TcpListenerStream::new(listener);
 
     println!("[https] server starting on https://0.0.0.0:443");
 
-    let tls_acceptor = tls_acceptor.clone();
-    let tls_incoming = incoming
-        .filter_map(move |conn| {
-            let tls_acceptor = tls_acceptor.clone();
-            async move {
-                match conn {
-                    Ok(stream) => match tls_acceptor.accept(stream).await {
-                        Ok(tls_stream) => Some(Ok(tls_stream)),
-                        Err(err) => {
-                            eprintln!("[https] TLS handshake failed: {err}");
-                            None
-                        }
-                    },
-                    Err(err) => {
-                        eprintln!("[https] TCP accept error: {err}");
-                        None
-                    }
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(err) => {
+                eprintln!("[https] TCP accept error: {err}");
+                continue;
+            }
+        };
+
+        let tls_acceptor = tls_acceptor.clone();
+        let router = router.clone();
+        tokio::spawn(async move {
+            let tls_stream = match tls_acceptor.accept(stream).await {
+                Ok(tls_stream) => tls_stream,
+                Err(err) => {
+                    eprintln!("[https] TLS handshake failed ({peer}): {err}");
+                    return;
                 }
+            };
+
+            // Le connessioni devono supportare l'upgrade per servire wss://
+            let service = TowerToHyperService::new(router);
+            if let Err(err) = ConnectionBuilder::new(TokioExecutor::new())
+                .serve_connection_with_upgrades(TokioIo::new(tls_stream), service)
+                .await
+            {
+                eprintln!("[https] connection error ({peer}): {err}");
             }
-        })
-        .map(|stream| stream.map_err(|err| IoError::new(ErrorKind::Other, err)));
-
-    axum::serve(tls_incoming, service)
-        .await
-        .map_err(|err| -> Box<dyn std::error::Error> { Box::new(err) })?;
-
-    Ok(())
+        });
+    }
 }
 
 // ===========================================
@@ -1,6 +1,8 @@
 mod models;
 mod https_server;
 mod auth;
+mod rooms;
+mod websocket;
 
 use https_server::{run_https_server, run_dev_server};
 
@@ -31,7 +33,7 @@ async fn main() {
         println!("📖 Usage:");
         println!("  cargo run -- --dev     # Run HTTP development server");
         println!("  cargo run -- --https   # Run HTTPS production server");
-        println!("");
+        println!();
         println!("🔐 For HTTPS, make sure you have certificates in ./certs/");
         println!("   Run: ./scripts/generate_dev_certs.sh");
     }
@@ -0,0 +1,383 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use super::{
+    card::{Card, Suit},
+    commands::Command,
+    expert::ExpertBot,
+    game::{GamePhase, GameState},
+    log::{LogEntry, LogRecord},
+    player::Player,
+    ranking::WildDeclaration,
+    rules::GameRules,
+};
+
+// ===========================================
+// BOT
+// ===========================================
+//
+// Un bot occupa un posto come un giocatore qualsiasi e agisce con gli stessi
+// comandi che un client invia sul socket (`make_prediction`, `play_card`).
+// Vede solo cio che vedrebbe un umano al suo posto: la propria mano (nel round
+// alla cieca quelle degli avversari), la briscola e le carte gia giocate.
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BotDifficulty {
+    #[default]
+    Easy, // regole empiriche sulla forza delle carte
+    Strong, // stima la probabilita di prendere ogni mano
+    Expert, // simula il resto del round su mani avversarie campionate
+}
+
+impl BotDifficulty {
+    pub fn strategy(self, rules: &GameRules) -> Box<dyn Bot> {
+        match self {
+            BotDifficulty::Easy => Box::new(EasyBot),
+            BotDifficulty::Strong => Box::new(StrongBot),
+            BotDifficulty::Expert => Box::new(ExpertBot {
+                budget: Duration::from_millis(rules.bot_time_budget_ms),
+            }),
+        }
+    }
+}
+
+//  Strategia di un bot. Il comando da inviare lo costruisce `decide`,
+//  che corregge la previsione alla piu vicina ammessa dalle regole.
+pub trait Bot {
+    //  Prese che il bot pensa di fare in questo round
+    fn prediction(&self, view: &BotView) -> u8;
+    //  Carta da giocare tra quelle ammesse, con la dichiarazione se e la matta
+    fn card(&self, view: &BotView, legal: &[Card]) -> (Card, WildDeclaration);
+}
+
+//  Tocca al giocatore indicato? A buste chiuse parla chiunque non abbia previsto
+fn is_turn_of(game: &GameState, player_id: &str) -> bool {
+    let Some(player) = game.players.iter().find(|p| p.id == player_id) else {
+        return false;
+    };
+    match game.phase {
+        GamePhase::Predicting if game.rules.sealed_predictions() => {
+            player.is_active() && player.prediction.is_none()
+        }
+        GamePhase::Predicting | GamePhase::Playing => game.current_player_id() == Some(player_id),
+        _ => false,
+    }
+}
+
+//  Comando che il bot invierebbe adesso, `None` se non tocca a lui
+pub fn decide(bot: &dyn Bot, game: &GameState, player_id: &str) -> Option<Command> {
+    if !is_turn_of(game, player_id) {
+        return None;
+    }
+    let view = BotView { game, player_id };
+    let me = view.me()?;
+    match game.phase {
+        GamePhase::Predicting => {
+            let target = bot.prediction(&view);
+            let prediction = (0..=me.hand.len() as u8)
+                .filter(|&value| game.validate_prediction(player_id, value).is_ok())
+                .min_by_key(|value| value.abs_diff(target))?;
+            Some(Command::MakePrediction { prediction })
+        }
+        GamePhase::Playing => {
+            // Nel round alla cieca si gioca la carta coperta: se e la matta vale alta
+            if game.is_blind_round() {
+                return Some(Command::PlayCard { card: None, declaration: Some(WildDeclaration::High) });
+            }
+            let legal = game.legal_cards(player_id).ok()?;
+            if legal.is_empty() {
+                return None;
+            }
+            let (card, declaration) = bot.card(&view, &legal);
+            let declaration = game.rules.is_wild(&card).then_some(declaration);
+            Some(Command::PlayCard { card: Some(card), declaration })
+        }
+        _ => None,
+    }
+}
+
+impl GameState {
+    //  Primo bot che deve agire adesso
+    pub fn next_bot(&self) -> Option<String> {
+        self.players
+            .iter()
+            .find(|p| p.bot.is_some() && is_turn_of(self, &p.id))
+            .map(|p| p.id.clone())
+    }
+
+    //  Comando scelto dal bot indicato. Con il bot esperto puo richiedere fino a
+    //  `bot_time_budget_ms`: va chiamato su una copia, senza bloccare il tavolo.
+    pub fn bot_command(&self, bot_id: &str) -> Option<Command> {
+        let difficulty = self.players.iter().find(|p| p.id == bot_id)?.bot?;
+        decide(difficulty.strategy(&self.rules).as_ref(), self, bot_id)
+    }
+}
+
+// ===========================================
+// COSA VEDE IL BOT
+// ===========================================
+
+pub struct BotView<'a> {
+    pub game: &'a GameState,
+    pub player_id: &'a str,
+}
+
+impl<'a> BotView<'a> {
+    fn me(&self) -> Option<&Player> {
+        self.game.players.iter().find(|p| p.id == self.player_id)
+    }
+
+    pub fn hand(&self) -> &[Card] {
+        self.me().map(|p| p.hand.as_slice()).unwrap_or_default()
+    }
+
+    //  Prese ancora da fare per rispettare la previsione (negativo se gia superata)
+    pub fn tricks_needed(&self) -> i32 {
+        self.me()
+            .map_or(0, |p| p.prediction.unwrap_or(0) as i32 - p.tricks_won as i32)
+    }
+
+    //  Forza assoluta di una carta: la matta, poi il seme dominante, poi il valore
+    pub fn strength(&self, card: &Card) -> u8 {
+        let rules = &self.game.rules;
+        if rules.is_wild(card) {
+            return 40;
+        }
+        let dominant = self.game.trump_suit().is_some_and(|suit| suit == card.suit);
+        rules.card_ranking.value_rank(&card.value) + if dominant { 20 } else { 0 }
+    }
+
+    //  Voci del registro dall'inizio del round corrente
+    fn round_records(&self) -> &'a [LogRecord] {
+        let records = &self.game.log.records;
+        let start = records
+            .iter()
+            .rposition(|r| matches!(r.entry, LogEntry::RoundEnded { .. } | LogEntry::Started))
+            .map_or(0, |index| index + 1);
+        &records[start..]
+    }
+
+    //  Carte giocate nel round corrente, in ordine, ricavate dal registro
+    pub fn played_this_round(&self) -> Vec<(String, Card)> {
+        self.round_records()
+            .iter()
+            .filter_map(|r| match &r.entry {
+                LogEntry::CardPlayed { player_id, card, .. } => Some((player_id.clone(), card.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    //  Semi che un giocatore ha dimostrato di non avere: non ha risposto al seme
+    //  di uscita (e, se obbligatorio, nemmeno con la briscola). La matta non conta.
+    pub fn observed_voids(&self) -> Vec<(String, Suit)> {
+        let rules = &self.game.rules;
+        let trump = self.game.trump_suit();
+        let mut voids: Vec<(String, Suit)> = Vec::new();
+        let mut leading: Option<Suit> = None;
+        for record in self.round_records() {
+            match &record.entry {
+                LogEntry::CardPlayed { player_id, card, .. } => {
+                    // Il seme di uscita lo fissa la prima carta non matta
+                    let Some(lead) = leading.clone() else {
+                        leading = (!rules.is_wild(card)).then(|| card.suit.clone());
+                        continue;
+                    };
+                    if card.suit == lead || rules.is_wild(card) {
+                        continue;
+                    }
+                    voids.push((player_id.clone(), lead));
+                    if let Some(trump) = trump.clone().filter(|t| rules.must_trump_if_void && card.suit != *t) {
+                        voids.push((player_id.clone(), trump));
+                    }
+                }
+                LogEntry::TrickWon { .. } => leading = None,
+                _ => {}
+            }
+        }
+        voids
+    }
+
+    //  Carte di cui il bot non conosce la posizione: mano altrui o mazzo
+    pub fn unseen(&self) -> Vec<Card> {
+        let mut unseen: Vec<Card> = (0..self.game.rules.deck_count())
+            .flat_map(|_| Card::new_deck())
+            .collect();
+        let mut known: Vec<Card> = self.played_this_round().into_iter().map(|(_, c)| c).collect();
+        known.extend(self.game.trump_card.clone());
+        for view in self.game.hand_views(self.player_id) {
+            known.extend(view.cards.into_iter().flatten());
+        }
+        for card in known {
+            if let Some(index) = unseen.iter().position(|c| *c == card) {
+                unseen.swap_remove(index);
+            }
+        }
+        unseen
+    }
+
+    //  Avversari che devono ancora giocare nella mano dopo il bot
+    pub fn opponents_to_play(&self) -> usize {
+        self.game
+            .active_player_count()
+            .saturating_sub(self.game.current_turn_cards.len() + 1)
+    }
+
+    //  Carta che sta prendendo la mano in corso; la matta non conta
+    pub fn trick_best(&self, leading: &Suit) -> Option<&'a Card> {
+        let game = self.game;
+        game.current_turn_cards
+            .iter()
+            .filter(|(id, _)| game.wild_declaration(id).is_none())
+            .map(|(_, c)| c)
+            .fold(None, |best: Option<&Card>, c| match best {
+                Some(b) if !game.card_beats(c, b, leading) => Some(b),
+                _ => Some(c),
+            })
+    }
+
+    //  Probabilita che la carta prenda la mano in corso (o una mano aperta dal bot):
+    //  deve battere le carte gia giocate e nessuno dopo deve avere di meglio.
+    //  Ogni avversario viene trattato come una carta estratta a caso tra quelle non viste.
+    pub fn win_probability(&self, card: &Card) -> f32 {
+        let game = self.game;
+        let trick = &game.current_turn_cards;
+        let high_wild = trick
+            .iter()
+            .any(|(id, _)| game.wild_declaration(id) == Some(WildDeclaration::High));
+        if game.rules.is_wild(card) {
+            return if high_wild { 0.0 } else { 1.0 };
+        }
+        if high_wild {
+            return 0.0;
+        }
+
+        let leading = game.leading_suit.clone().unwrap_or_else(|| card.suit.clone());
+        if self.trick_best(&leading).is_some_and(|b| !game.card_beats(card, b, &leading)) {
+            return 0.0;
+        }
+
+        let unseen = self.unseen();
+        if unseen.is_empty() {
+            return 1.0;
+        }
+        let stronger = unseen
+            .iter()
+            .filter(|u| game.rules.is_wild(u) || game.card_beats(u, card, &leading))
+            .count();
+        let safe = 1.0 - stronger as f32 / unseen.len() as f32;
+        safe.powi(self.opponents_to_play() as i32)
+    }
+
+    //  Round alla cieca: prova ogni carta che potrebbe essere quella coperta
+    //  contro le carte visibili degli avversari, in ordine di gioco
+    pub fn blind_win_probability(&self) -> f32 {
+        let game = self.game;
+        let count = game.players.len();
+        let order: Vec<Option<Card>> = (0..count)
+            .map(|step| &game.players[(game.trick_leader + step) % count])
+            .filter(|p| p.is_active())
+            .map(|p| if p.id == self.player_id { None } else { p.hand.first().cloned() })
+            .collect();
+        let Some(own_seat) = (0..count)
+            .map(|step| &game.players[(game.trick_leader + step) % count])
+            .filter(|p| p.is_active())
+            .position(|p| p.id == self.player_id)
+        else {
+            return 0.0;
+        };
+
+        let unseen = self.unseen();
+        if unseen.is_empty() {
+            return 0.0;
+        }
+        let wins = unseen
+            .iter()
+            .filter(|own| {
+                let cards: Vec<&Card> = order.iter().map(|c| c.as_ref().unwrap_or(own)).collect();
+                let leading = &cards[0].suit;
+                let winner = (1..cards.len()).fold(0, |best, i| {
+                    if game.card_beats(cards[i], cards[best], leading) { i } else { best }
+                });
+                winner == own_seat
+            })
+            .count();
+        wins as f32 / unseen.len() as f32
+    }
+}
+
+// ===========================================
+// STRATEGIE
+// ===========================================
+
+//  Conta le carte alte e gioca la piu forte quando deve prendere, la piu debole altrimenti
+pub struct EasyBot;
+
+impl Bot for EasyBot {
+    fn prediction(&self, view: &BotView) -> u8 {
+        if view.game.is_blind_round() {
+            return 0;
+        }
+        // Assi, re (e tre nell'ordine della briscola), oltre al seme dominante dal fante in su
+        let high = |c: &&Card| {
+            let strength = view.strength(c);
+            (10..20).contains(&strength) || strength >= 28
+        };
+        view.hand().iter().filter(high).count() as u8
+    }
+
+    fn card(&self, view: &BotView, legal: &[Card]) -> (Card, WildDeclaration) {
+        let by_strength = |a: &&Card, b: &&Card| view.strength(a).cmp(&view.strength(b));
+        if view.tricks_needed() > 0 {
+            let card = legal.iter().max_by(by_strength).unwrap_or(&legal[0]);
+            (card.clone(), WildDeclaration::High)
+        } else {
+            let card = legal.iter().min_by(by_strength).unwrap_or(&legal[0]);
+            (card.clone(), WildDeclaration::Low)
+        }
+    }
+}
+
+//  Stima la probabilita di prendere con ogni carta usando il seme di uscita,
+//  la briscola e la gerarchia dei semi di `beats_custom` (Denari per primo)
+pub struct StrongBot;
+
+impl Bot for StrongBot {
+    fn prediction(&self, view: &BotView) -> u8 {
+        let expected = if view.game.is_blind_round() {
+            view.blind_win_probability()
+        } else {
+            view.hand().iter().map(|c| view.win_probability(c)).sum()
+        };
+        expected.round() as u8
+    }
+
+    fn card(&self, view: &BotView, legal: &[Card]) -> (Card, WildDeclaration) {
+        let scored: Vec<(&Card, f32, u8)> = legal
+            .iter()
+            .map(|c| (c, view.win_probability(c), view.strength(c)))
+            .collect();
+        let needed = view.tricks_needed();
+
+        let choice = if needed <= 0 {
+            // Evita la presa; a parita si libera della carta piu pericolosa
+            scored
+                .iter()
+                .min_by(|a, b| a.1.total_cmp(&b.1).then(b.2.cmp(&a.2)))
+        } else if needed as usize >= view.hand().len() {
+            // Deve prendere tutte le mani rimaste
+            scored.iter().max_by(|a, b| a.1.total_cmp(&b.1))
+        } else {
+            // La vincente piu economica, altrimenti conserva le carte forti
+            scored
+                .iter()
+                .filter(|s| s.1 >= 0.5)
+                .min_by_key(|s| s.2)
+                .or_else(|| scored.iter().min_by_key(|s| s.2))
+        };
+        let card = choice.map_or(&legal[0], |s| s.0);
+        let declaration = if needed > 0 { WildDeclaration::High } else { WildDeclaration::Low };
+        (card.clone(), declaration)
+    }
+}
@@ -1,6 +1,14 @@
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use serde::{Serialize, Deserialize};
+use std::fmt;
+use std::str::FromStr;
+
+use super::{ranking::CardRanking, schedule::RoundSchedule};
+
+//  Numero di carte nel mazzo italiano
+pub const DECK_SIZE: usize = 40;
 
 //  I quattro semi del mazzo italiano
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -44,8 +52,16 @@ pub struct Card {
 }
 
 impl Card {
-    //  Crea e mescola un mazzo completo (40 carte)
-    pub fn shuffle_deck() -> Vec<Card> {
+    //  Crea e mescola `decks` mazzi completi (40 carte ciascuno) a partire da un seed
+    pub fn shuffle_deck(seed: u64, decks: usize) -> Vec<Card> {
+        let mut deck: Vec<Card> = (0..decks).flat_map(|_| Card::new_deck()).collect();
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        deck.shuffle(&mut rng);
+        deck
+    }
+
+    //  Mazzo completo in ordine (40 carte)
+    pub fn new_deck() -> Vec<Card> {
         let mut deck = Vec::new();
 
         let suits = vec![
@@ -76,42 +92,219 @@ impl Card {
                 });
             }
         }
-
-        let mut rng = thread_rng();
-        deck.shuffle(&mut rng);
         deck
     }
 }
 
-//  Distribuisce carte a ciascun giocatore
-pub fn deal_cards(deck: &mut Vec<Card>, num_players: usize, cards_per_player: usize) -> Vec<Vec<Card>> {
+// ===========================================
+// NOTAZIONE DELLE CARTE
+// ===========================================
+//
+// Valore + seme, es. `7D` (Sette di Denari), `AC` (Asso di Coppe), `RB` (Re di Bastoni).
+// Valori: A 2 3 4 5 6 7 F(ante) C(avallo) R(e). Semi: D C S B.
+
+impl Suit {
+    pub fn symbol(&self) -> char {
+        match self {
+            Suit::Denari => 'D',
+            Suit::Coppe => 'C',
+            Suit::Spade => 'S',
+            Suit::Bastoni => 'B',
+        }
+    }
+
+    pub fn from_symbol(symbol: char) -> Option<Suit> {
+        match symbol.to_ascii_uppercase() {
+            'D' => Some(Suit::Denari),
+            'C' => Some(Suit::Coppe),
+            'S' => Some(Suit::Spade),
+            'B' => Some(Suit::Bastoni),
+            _ => None,
+        }
+    }
+}
+
+impl Value {
+    pub fn symbol(&self) -> char {
+        match self {
+            Value::Asso => 'A',
+            Value::Due => '2',
+            Value::Tre => '3',
+            Value::Quattro => '4',
+            Value::Cinque => '5',
+            Value::Sei => '6',
+            Value::Sette => '7',
+            Value::Fante => 'F',
+            Value::Cavallo => 'C',
+            Value::Re => 'R',
+        }
+    }
+
+    pub fn from_symbol(symbol: char) -> Option<Value> {
+        match symbol.to_ascii_uppercase() {
+            'A' => Some(Value::Asso),
+            '2' => Some(Value::Due),
+            '3' => Some(Value::Tre),
+            '4' => Some(Value::Quattro),
+            '5' => Some(Value::Cinque),
+            '6' => Some(Value::Sei),
+            '7' => Some(Value::Sette),
+            'F' => Some(Value::Fante),
+            'C' => Some(Value::Cavallo),
+            'R' => Some(Value::Re),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.value.symbol(), self.suit.symbol())
+    }
+}
+
+//  Carta non riconosciuta nella notazione
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCardError(pub String);
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Carta non valida: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseCardError {}
+
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(value), Some(suit), None) => Ok(Card {
+                value: Value::from_symbol(value).ok_or_else(|| ParseCardError(s.to_string()))?,
+                suit: Suit::from_symbol(suit).ok_or_else(|| ParseCardError(s.to_string()))?,
+            }),
+            _ => Err(ParseCardError(s.to_string())),
+        }
+    }
+}
+
+//  Nuovo seed casuale per una partita
+pub fn random_seed() -> u64 {
+    rand::thread_rng().gen()
+}
+
+//  Seed del mazzo per un round, derivato in modo deterministico dal seed della partita
+pub fn round_seed(game_seed: u64, round_number: usize) -> u64 {
+    let mut rng = ChaCha8Rng::seed_from_u64(game_seed);
+    rng.set_stream(round_number as u64);
+    rng.next_u64()
+}
+
+//  Errori possibili durante la distribuzione
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DealError {
+    NoPlayers,
+    NotEnoughCards { needed: usize, available: usize },
+    RoundOutOfRange { round_number: usize, starting_cards: usize },
+}
+
+impl fmt::Display for DealError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DealError::NoPlayers => write!(f, "Nessun giocatore a cui distribuire"),
+            DealError::NotEnoughCards { needed, available } => write!(
+                f,
+                "Carte insufficienti: servono {} carte, nel mazzo ce ne sono {}",
+                needed, available
+            ),
+            DealError::RoundOutOfRange { round_number, starting_cards } => write!(
+                f,
+                "Round {} non valido per una partita da {} carte",
+                round_number, starting_cards
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DealError {}
+
+//  Mano iniziale massima per un numero di giocatori e di mazzi
+pub fn max_starting_cards(num_players: usize, decks: usize) -> usize {
+    if num_players == 0 {
+        return 0;
+    }
+    DECK_SIZE * decks / num_players
+}
+
+//  Distribuisce carte a ciascun giocatore (mai mani incomplete)
+pub fn deal_cards(deck: &mut Vec<Card>, num_players: usize, cards_per_player: usize) -> Result<Vec<Vec<Card>>, DealError> {
+    if num_players == 0 {
+        return Err(DealError::NoPlayers);
+    }
+    let needed = num_players * cards_per_player;
+    if needed > deck.len() {
+        return Err(DealError::NotEnoughCards { needed, available: deck.len() });
+    }
+
     let mut hands = vec![Vec::new(); num_players];
-    
     for _ in 0..cards_per_player {
-        for player in 0..num_players {
+        for hand in hands.iter_mut() {
             if let Some(card) = deck.pop() {
-                hands[player].push(card);
+                hand.push(card);
             }
         }
     }
-    hands
+    Ok(hands)
 }
 
-//  Distribuisce le carte per round (ogni round diminuisce di una carta)
-pub fn deal_round(deck: &mut Vec<Card>, num_players: usize, round_number: usize, starting_cards: usize) -> Vec<Vec<Card>> {
-    let cards_per_player = starting_cards - (round_number - 1);
+//  Distribuisce le carte per round secondo la sequenza scelta
+pub fn deal_round(
+    deck: &mut Vec<Card>,
+    num_players: usize,
+    round_number: usize,
+    starting_cards: usize,
+    schedule: &RoundSchedule,
+) -> Result<Vec<Vec<Card>>, DealError> {
+    let cards_per_player = schedule
+        .cards_for_round(round_number, starting_cards)
+        .filter(|cards| *cards > 0)
+        .ok_or(DealError::RoundOutOfRange { round_number, starting_cards })?;
     deal_cards(deck, num_players, cards_per_player)
 }
 
 impl Card {
-    pub fn beats_custom(&self, other: &Card, leading_suit: &Suit) -> bool {
-        // Se self è Denari e l'altra no → vince self
-        if suit_strength(&self.suit) > suit_strength(&other.suit) {
-            return true;
+    pub fn beats_custom(
+        &self,
+        other: &Card,
+        leading_suit: &Suit,
+        trump: Option<&Suit>,
+        ranking: &CardRanking,
+    ) -> bool {
+        // Con una briscola scoperta il suo seme batte tutti gli altri
+        if let Some(trump) = trump {
+            if self.suit == *trump && other.suit != *trump {
+                return true;
+            }
+            if other.suit == *trump && self.suit != *trump {
+                return false;
+            }
+            if self.suit != other.suit {
+                return self.suit == *leading_suit;
+            }
+        }
+        // Con la gerarchia dei semi tra semi diversi decide solo la gerarchia,
+        // anche contro il seme di uscita (altrimenti l'esito dipende dall'ordine)
+        let ranks = (ranking.suit_rank(&self.suit), ranking.suit_rank(&other.suit));
+        if let (Some(own), Some(theirs)) = ranks {
+            if trump.is_none() && own != theirs {
+                return own > theirs;
+            }
         }
         // Se stessa forza e stesso seme → confronto per valore
         if self.suit == other.suit {
-            return self.value > other.value;
+            return ranking.value_rank(&self.value) > ranking.value_rank(&other.value);
         }
         // Se semi diversi ma self ha il seme dominante
         if self.suit == *leading_suit && other.suit != *leading_suit {
@@ -120,3 +313,92 @@ impl Card {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::ranking::{SuitOrder, ValueOrder};
+    use super::*;
+    use std::collections::HashSet;
+
+    fn card(text: &str) -> Card {
+        text.parse().unwrap()
+    }
+
+    //  `a` batte `b` con il seme di uscita, la briscola e la gerarchia indicati
+    fn beats(a: &str, b: &str, leading: Suit, trump: Option<Suit>, ranking: CardRanking) -> bool {
+        card(a).beats_custom(&card(b), &leading, trump.as_ref(), &ranking)
+    }
+
+    fn ranking(values: ValueOrder, suits: SuitOrder) -> CardRanking {
+        CardRanking { values, suits }
+    }
+
+    #[test]
+    fn notation_round_trips_every_card() {
+        let deck = Card::new_deck();
+        assert_eq!(deck.len(), DECK_SIZE);
+        let symbols: HashSet<String> = deck.iter().map(Card::to_string).collect();
+        assert_eq!(symbols.len(), DECK_SIZE, "due carte con la stessa notazione");
+        for card in &deck {
+            assert_eq!(card.to_string().parse::<Card>().as_ref(), Ok(card));
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_cards() {
+        for text in ["", "A", "Z#", "AD1"] {
+            assert!(text.parse::<Card>().is_err(), "{text:?} accettata");
+        }
+    }
+
+    #[test]
+    fn value_order_within_a_suit() {
+        let standard = CardRanking::default();
+        assert!(beats("AC", "RC", Suit::Coppe, None, standard));
+        assert!(beats("RC", "3C", Suit::Coppe, None, standard));
+        assert!(!beats("3C", "RC", Suit::Coppe, None, standard));
+
+        // Nell'ordine della briscola il tre segue l'asso e batte il re
+        let briscola = ranking(ValueOrder::Briscola, SuitOrder::LeadingOnly);
+        assert!(beats("AC", "3C", Suit::Coppe, None, briscola));
+        assert!(beats("3C", "RC", Suit::Coppe, None, briscola));
+        assert!(!beats("RC", "3C", Suit::Coppe, None, briscola));
+    }
+
+    #[test]
+    fn suit_hierarchy_or_leading_suit_only() {
+        // Con la gerarchia dei semi i Denari battono il seme di uscita
+        let standard = CardRanking::default();
+        assert!(beats("2D", "AS", Suit::Spade, None, standard));
+        assert!(!beats("AS", "2D", Suit::Spade, None, standard));
+
+        // Senza gerarchia conta solo il seme di uscita
+        let leading_only = ranking(ValueOrder::Standard, SuitOrder::LeadingOnly);
+        assert!(!beats("2D", "AS", Suit::Spade, None, leading_only));
+        assert!(beats("2S", "AD", Suit::Spade, None, leading_only));
+        assert!(!beats("AD", "RC", Suit::Spade, None, leading_only));
+    }
+
+    #[test]
+    fn trump_beats_the_leading_suit() {
+        for suits in [SuitOrder::Standard, SuitOrder::LeadingOnly] {
+            let ranking = ranking(ValueOrder::Standard, suits);
+            assert!(beats("2B", "AS", Suit::Spade, Some(Suit::Bastoni), ranking), "{suits:?}");
+            assert!(!beats("AS", "2B", Suit::Spade, Some(Suit::Bastoni), ranking), "{suits:?}");
+            // La briscola batte anche il seme piu forte della gerarchia
+            assert!(beats("2B", "AD", Suit::Spade, Some(Suit::Bastoni), ranking), "{suits:?}");
+            assert!(beats("3B", "2B", Suit::Spade, Some(Suit::Bastoni), ranking), "{suits:?}");
+            // Fuori seme e fuori briscola non si prende
+            assert!(!beats("AD", "2S", Suit::Spade, Some(Suit::Bastoni), ranking), "{suits:?}");
+        }
+    }
+
+    #[test]
+    fn identical_cards_do_not_beat_each_other() {
+        for suits in [SuitOrder::Standard, SuitOrder::LeadingOnly] {
+            let ranking = ranking(ValueOrder::Standard, suits);
+            assert!(!beats("AS", "AS", Suit::Spade, None, ranking), "{suits:?}");
+            assert!(!beats("2B", "2B", Suit::Spade, Some(Suit::Bastoni), ranking), "{suits:?}");
+        }
+    }
+}
@@ -0,0 +1,471 @@
+use super::{
+    bots::BotDifficulty,
+    card::Card,
+    error::GameError,
+    events::{
+        Audience, BotSeat, Envelope, GameEvent, LivesLine, PlayedCard, PredictionLine, RoundStart,
+        ScoreLine, SeedReveal, TableSnapshot, TrickStanding,
+    },
+    game::{GamePhase, GameState},
+    log::LogEntry,
+    player::Player,
+    ranking::WildDeclaration,
+};
+
+// ===========================================
+// COMANDI
+// ===========================================
+//
+// Le azioni che un partecipante puo chiedere al tavolo. Ogni comando accettato
+// restituisce gli eventi da consegnare; se rifiutato, l'errore va solo a chi
+// lo ha inviato.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    StartGame,
+    MakePrediction { prediction: u8 },
+    // Senza carta si gioca quella coperta del round alla cieca
+    PlayCard { card: Option<Card>, declaration: Option<WildDeclaration> },
+    ContributeEntropy { entropy: String },
+    LegalCards,
+    PauseGame,
+    ResumeGame,
+    // Solo per chi ospita il tavolo
+    AddBot { difficulty: BotDifficulty },
+    SetBotDifficulty { bot_id: String, difficulty: BotDifficulty },
+}
+
+impl GameState {
+    //  Fa sedere il giocatore, o lo aggiunge agli spettatori se non ci sono posti
+    pub fn join(&mut self, player_id: &str) -> Result<Vec<Envelope>, GameError> {
+        let player = Player::new(player_id.to_string());
+        if self.seat_available() {
+            self.add_player(player)?;
+            Ok(self.joined(player_id, false, None))
+        } else {
+            self.add_spectator(player)?;
+            Ok(self.joined(player_id, true, None))
+        }
+    }
+
+    //  Fa sedere un bot; i bot non entrano mai come spettatori
+    pub(super) fn seat_bot(
+        &mut self,
+        player_id: String,
+        difficulty: BotDifficulty,
+    ) -> Result<Vec<Envelope>, GameError> {
+        let mut player = Player::new(player_id.clone());
+        player.bot = Some(difficulty);
+        self.add_player(player)?;
+        Ok(self.joined(&player_id, false, Some(difficulty)))
+    }
+
+    //  Registra l'ingresso, saluta il nuovo arrivato e avvia la partita se il tavolo e pieno
+    fn joined(&mut self, player_id: &str, spectator: bool, bot: Option<BotDifficulty>) -> Vec<Envelope> {
+        self.log.append(LogEntry::Joined { player_id: player_id.to_string(), spectator, bot });
+
+        let mut events = Vec::new();
+        if bot.is_none() {
+            let welcome = GameEvent::Welcome(Box::new(self.snapshot(player_id, spectator)));
+            self.emit(&mut events, Audience::Player(player_id.to_string()), welcome);
+        }
+        self.emit(
+            &mut events,
+            Audience::Everyone,
+            GameEvent::PlayerJoined { id: player_id.to_string(), spectator, bot },
+        );
+
+        if self.should_auto_start() {
+            let _ = self.begin(&mut events);
+        }
+        events
+    }
+
+    //  Prima dell'inizio il posto si libera; a partita iniziata resta occupato
+    //  e le mosse del giocatore uscito le fa il server (`expire_turns`)
+    pub fn leave(&mut self, player_id: &str) -> Vec<Envelope> {
+        let started = self.phase != GamePhase::Waiting;
+        match self.players.iter_mut().find(|p| p.id == player_id) {
+            Some(player) if started => player.away = true,
+            _ => self.remove_player(player_id),
+        }
+        self.log.append(LogEntry::Left { player_id: player_id.to_string() });
+        let mut events = Vec::new();
+        self.emit(&mut events, Audience::Everyone, GameEvent::PlayerLeft { id: player_id.to_string() });
+        events
+    }
+
+    pub fn handle(&mut self, player_id: &str, command: Command) -> Result<Vec<Envelope>, GameError> {
+        let mut events = Vec::new();
+        match command {
+            Command::StartGame => {
+                self.require_host(player_id)?;
+                self.begin(&mut events)?;
+            }
+            Command::MakePrediction { prediction } => {
+                self.predict(&mut events, player_id, prediction, false)?;
+            }
+            Command::PlayCard { card, declaration } => {
+                let card = card
+                    .or_else(|| self.blind_card(player_id))
+                    .ok_or(GameError::InvalidPayload { action: "play_card".to_string() })?;
+                self.play(&mut events, player_id, card, declaration, false)?;
+            }
+            Command::ContributeEntropy { entropy } => {
+                self.contribute_entropy(player_id, entropy.clone())?;
+                self.log.append(LogEntry::EntropyContributed {
+                    player_id: player_id.to_string(),
+                    entropy: entropy.clone(),
+                });
+                let event = GameEvent::EntropyContributed {
+                    player_id: player_id.to_string(),
+                    entropy,
+                    next_seed_commitment: self.next_seed_commitment(),
+                };
+                self.emit(&mut events, Audience::Everyone, event);
+            }
+            Command::LegalCards => {
+                // La carta coperta non va rivelata al suo proprietario
+                let blind = self.is_blind_round();
+                let cards = self
+                    .legal_cards(player_id)?
+                    .into_iter()
+                    .map(|c| (!blind).then_some(c))
+                    .collect();
+                self.emit(
+                    &mut events,
+                    Audience::Player(player_id.to_string()),
+                    GameEvent::LegalCards { cards },
+                );
+            }
+            Command::PauseGame => {
+                self.require_seated(player_id)?;
+                self.pause()?;
+                self.log.append(LogEntry::Paused { player_id: player_id.to_string() });
+                let event = GameEvent::GamePaused { player_id: player_id.to_string() };
+                self.emit(&mut events, Audience::Everyone, event);
+            }
+            Command::ResumeGame => {
+                self.require_seated(player_id)?;
+                self.resume()?;
+                self.log.append(LogEntry::Resumed { player_id: player_id.to_string() });
+                let event = GameEvent::GameResumed { player_id: player_id.to_string() };
+                self.emit(&mut events, Audience::Everyone, event);
+                self.turn_changed(&mut events);
+            }
+            Command::AddBot { difficulty } => {
+                self.require_host(player_id)?;
+                // Id deterministico: il replay ricrea lo stesso bot
+                let bot_id = format!("bot-{}", self.log.records.len());
+                events = self.seat_bot(bot_id, difficulty)?;
+            }
+            Command::SetBotDifficulty { bot_id, difficulty } => {
+                self.require_host(player_id)?;
+                let bot = self
+                    .players
+                    .iter_mut()
+                    .find(|p| p.id == bot_id)
+                    .ok_or(GameError::PlayerNotFound)?;
+                let Some(current) = bot.bot.as_mut() else {
+                    return Err(GameError::NotABot);
+                };
+                *current = difficulty;
+                self.log.append(LogEntry::BotDifficultyChanged {
+                    player_id: player_id.to_string(),
+                    bot_id: bot_id.clone(),
+                    difficulty,
+                });
+                self.emit(&mut events, Audience::Everyone, GameEvent::BotDifficultyChanged { bot_id, difficulty });
+            }
+        }
+        Ok(events)
+    }
+
+    //  Avvia la partita e distribuisce il primo round
+    pub(super) fn begin(&mut self, events: &mut Vec<Envelope>) -> Result<(), GameError> {
+        self.start_game()?;
+        self.log.append(LogEntry::Started);
+        self.emit(events, Audience::Everyone, GameEvent::GameStarted {});
+        self.round_start_events(events);
+        Ok(())
+    }
+
+    //  Gli spettatori non possono avviare, sospendere o riprendere la partita
+    fn require_seated(&self, player_id: &str) -> Result<(), GameError> {
+        if self.players.iter().all(|p| p.id != player_id) {
+            return Err(GameError::PlayerNotFound);
+        }
+        Ok(())
+    }
+
+    fn require_host(&self, player_id: &str) -> Result<(), GameError> {
+        if self.host_id() != Some(player_id) {
+            return Err(GameError::NotHost);
+        }
+        Ok(())
+    }
+
+    //  Mosse d'ufficio per chiunque abbia esaurito il tempo o lasciato il tavolo
+    pub fn expire_turns(&mut self) -> Vec<Envelope> {
+        let player_ids: Vec<String> = self.players.iter().map(|p| p.id.clone()).collect();
+        player_ids.iter().flat_map(|id| self.expire_turn(id)).collect()
+    }
+
+    //  Tempo scaduto: previsione piu bassa ammessa o prima carta giocabile
+    //  (la matta giocata d'ufficio vale come alta)
+    pub fn expire_turn(&mut self, player_id: &str) -> Vec<Envelope> {
+        let mut events = Vec::new();
+        if !self.turn_expired(player_id) {
+            return events;
+        }
+        match self.phase {
+            GamePhase::Predicting => {
+                let max = self.starting_cards as u8;
+                for value in 0..=max {
+                    if self.predict(&mut events, player_id, value, true).is_ok() {
+                        break;
+                    }
+                }
+            }
+            GamePhase::Playing => {
+                let card = self
+                    .legal_cards(player_id)
+                    .ok()
+                    .and_then(|cards| cards.into_iter().next());
+                if let Some(card) = card {
+                    let _ = self.play(&mut events, player_id, card, Some(WildDeclaration::High), true);
+                }
+            }
+            _ => {}
+        }
+        events
+    }
+
+    //  Stato del tavolo visto dal partecipante indicato
+    pub fn snapshot(&self, player_id: &str, spectator: bool) -> TableSnapshot {
+        let (hand, other_hands) = self.visible_hands(player_id);
+        TableSnapshot {
+            player_id: player_id.to_string(),
+            spectator,
+            rules: self.rules.clone(),
+            round_number: self.round_number,
+            starting_cards: self.starting_cards,
+            hand,
+            blind: self.is_blind_round(),
+            other_hands,
+            players: self.players.iter().map(|p| p.id.clone()).collect(),
+            host: self.host_id().map(str::to_string),
+            bots: self
+                .players
+                .iter()
+                .filter_map(|p| p.bot.map(|difficulty| BotSeat { player_id: p.id.clone(), difficulty }))
+                .collect(),
+            scores: self
+                .players
+                .iter()
+                .map(|p| ScoreLine {
+                    player_id: p.id.clone(),
+                    score: p.score,
+                    lives: p.lives,
+                    eliminated: !p.is_active(),
+                })
+                .collect(),
+            turn: self
+                .current_turn_cards
+                .iter()
+                .map(|(id, card)| PlayedCard { player_id: id.clone(), card: card.clone() })
+                .collect(),
+            current_player: self.current_player_id().map(str::to_string),
+            dealer: self.dealer_id().map(str::to_string),
+            trump_card: self.trump_card.clone(),
+            trump_suit: (self.phase != GamePhase::Waiting).then(|| self.trump_suit()).flatten(),
+            seed_commitment: (self.phase != GamePhase::Waiting).then(|| self.seed_commitment()),
+            next_seed_commitment: self.next_seed_commitment(),
+        }
+    }
+
+    fn emit(&self, events: &mut Vec<Envelope>, audience: Audience, event: GameEvent) {
+        events.push(Envelope { audience, phase: self.phase, event });
+    }
+
+    //  Applica una previsione e notifica il tavolo
+    pub(super) fn predict(
+        &mut self,
+        events: &mut Vec<Envelope>,
+        player_id: &str,
+        value: u8,
+        auto: bool,
+    ) -> Result<(), GameError> {
+        self.make_prediction(player_id, value)?;
+        self.log.append(LogEntry::Prediction { player_id: player_id.to_string(), prediction: value, auto });
+        // A buste chiuse si annuncia solo chi ha parlato, i valori alla fine
+        let sealed = self.rules.sealed_predictions();
+        let event = GameEvent::PredictionMade {
+            player_id: player_id.to_string(),
+            prediction: (!sealed).then_some(value),
+            sealed,
+            auto,
+        };
+        self.emit(events, Audience::Everyone, event);
+        if sealed && self.predictions_complete() {
+            let predictions = self
+                .players
+                .iter()
+                .filter(|p| p.is_active())
+                .map(|p| PredictionLine { player_id: p.id.clone(), prediction: p.prediction })
+                .collect();
+            self.emit(events, Audience::Everyone, GameEvent::PredictionsRevealed { predictions });
+        }
+        self.turn_changed(events);
+        Ok(())
+    }
+
+    //  Gioca una carta, notifica il tavolo e chiude la mano se completa
+    pub(super) fn play(
+        &mut self,
+        events: &mut Vec<Envelope>,
+        player_id: &str,
+        card: Card,
+        declaration: Option<WildDeclaration>,
+        auto: bool,
+    ) -> Result<(), GameError> {
+        self.play_card(player_id, card.clone(), declaration)?;
+        self.log.append(LogEntry::CardPlayed {
+            player_id: player_id.to_string(),
+            card: card.clone(),
+            declaration: self.wild_declaration(player_id),
+            auto,
+        });
+        let event = GameEvent::CardPlayed {
+            player_id: player_id.to_string(),
+            card,
+            declaration: self.wild_declaration(player_id),
+            auto,
+            blind: self.is_blind_round(),
+        };
+        self.emit(events, Audience::Everyone, event);
+        self.hand_updated(events, player_id);
+
+        let player_count = self.active_player_count();
+        if player_count > 0 && self.current_turn_cards.len() == player_count {
+            self.finalize_turn(events);
+        } else {
+            self.turn_changed(events);
+        }
+        Ok(())
+    }
+
+    fn finalize_turn(&mut self, events: &mut Vec<Envelope>) {
+        let cards = self.current_turn_cards.clone();
+        let Some(winner_id) = self.end_turn() else {
+            return;
+        };
+        self.log.append(LogEntry::TrickWon { player_id: winner_id.clone(), cards });
+        let standings = self
+            .players
+            .iter()
+            .map(|p| TrickStanding {
+                player_id: p.id.clone(),
+                tricks_won: p.tricks_won,
+                score: p.score,
+                lives: p.lives,
+            })
+            .collect();
+        // L'hash in testa al registro ancora la catena: i client possono
+        // confrontarlo con il registro scaricato a fine partita
+        let log_head = self.log.head();
+        self.emit(events, Audience::Everyone, GameEvent::TurnEnded { winner_id, standings, log_head });
+
+        if !self.is_round_over() {
+            self.turn_changed(events);
+            return;
+        }
+
+        // Rivela il seed del round per la verifica della distribuzione
+        let seed_reveal = SeedReveal {
+            round_seed: self.round_seed(),
+            seed_commitment: self.seed_commitment(),
+            entropy: self.round_entropy.clone(),
+            deck_seed: self.deck_seed(),
+        };
+        let results = self.end_round().to_vec();
+        self.log.append(LogEntry::RoundEnded {
+            round_number: self.round_number,
+            results: results.clone(),
+        });
+        let log_head = self.log.head();
+        self.emit(
+            events,
+            Audience::Everyone,
+            GameEvent::RoundEnded { results, seed_reveal, log_head: log_head.clone() },
+        );
+
+        if self.phase == GamePhase::Finished {
+            // Il seed rivelato a fine partita permette di rigenerarla
+            let event = GameEvent::GameOver {
+                standings: self.final_standings(),
+                ranked: !self.rules.friendly_mode,
+                seed: self.seed,
+                log_head,
+            };
+            self.emit(events, Audience::Everyone, event);
+        } else if self.next_round().is_ok() {
+            self.round_start_events(events);
+        }
+    }
+
+    //  Inizio di un round: dati pubblici, mani a ciascun giocatore, turno
+    fn round_start_events(&self, events: &mut Vec<Envelope>) {
+        let round_start = RoundStart {
+            round_number: self.round_number,
+            starting_cards: self.starting_cards,
+            cards_per_player: self.cards_this_round(),
+            total_rounds: (!self.rules.uses_lives()).then(|| self.total_rounds()),
+            round_schedule: self.rules.round_schedule,
+            dealer: self.dealer_id().map(str::to_string),
+            trump_card: self.trump_card.clone(),
+            trump_suit: self.trump_suit(),
+            lives: self
+                .players
+                .iter()
+                .map(|p| LivesLine { player_id: p.id.clone(), lives: p.lives, eliminated: !p.is_active() })
+                .collect(),
+            seed_commitment: self.seed_commitment(),
+            entropy: self.round_entropy.clone(),
+            next_seed_commitment: self.next_seed_commitment(),
+        };
+        self.emit(events, Audience::Everyone, GameEvent::RoundStarted(Box::new(round_start)));
+
+        for player in &self.players {
+            self.hand_updated(events, &player.id);
+        }
+        self.turn_changed(events);
+    }
+
+    //  Mano aggiornata; nel round alla cieca la propria carta arriva coperta
+    //  insieme alle carte degli avversari
+    fn hand_updated(&self, events: &mut Vec<Envelope>, player_id: &str) {
+        if self.players.iter().all(|p| p.id != player_id) {
+            return;
+        }
+        let (hand, other_hands) = self.visible_hands(player_id);
+        let event = GameEvent::HandUpdated {
+            player_id: player_id.to_string(),
+            hand,
+            blind: self.is_blind_round(),
+            other_hands,
+        };
+        self.emit(events, Audience::Player(player_id.to_string()), event);
+    }
+
+    //  A chi tocca agire
+    fn turn_changed(&self, events: &mut Vec<Envelope>) {
+        let event = GameEvent::TurnChanged {
+            player_id: self.current_player_id().map(str::to_string),
+            dealer: self.dealer_id().map(str::to_string),
+            trick_leader: self.trick_leader_id().map(str::to_string),
+            time_limit: self.turn_time_limit(),
+        };
+        self.emit(events, Audience::Everyone, event);
+    }
+}
@@ -0,0 +1,213 @@
+use serde_json::{json, Value};
+use std::fmt;
+
+use super::{
+    card::{DealError, Suit},
+    game::GamePhase,
+};
+
+// ===========================================
+// GAME ERROR
+// ===========================================
+//
+// Ogni errore ha un codice stabile (`code`) pensato per client e bot,
+// un messaggio leggibile (`message`) e dettagli strutturati (`details`).
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameError {
+    // Regole del tavolo
+    InvalidRules { field: &'static str, reason: String },
+    // Giocatori e posti
+    PlayerNotFound,
+    PlayerAlreadyJoined,
+    GameAlreadyStarted,
+    TableFull { max_players: usize },
+    SpectatorsNotAllowed,
+    NotEnoughPlayers { required: usize, current: usize },
+    NotHost,
+    NotABot,
+    TableClosed,
+    // Fasi e turni
+    InvalidPhase { current: GamePhase, expected: GamePhase },
+    InvalidTransition { from: GamePhase, to: GamePhase },
+    UnknownPausedPhase,
+    NotYourTurn { current_player: Option<String> },
+    RoundNotOver,
+    GameAlreadyOver,
+    // Previsioni
+    PredictionTooHigh { max: u8 },
+    PredictionAlreadyMade,
+    ForbiddenPrediction { forbidden: u8 },
+    // Carte
+    CardNotInHand,
+    MustFollowSuit { suit: Suit },
+    MustPlayTrump { suit: Suit },
+    WildDeclarationRequired,
+    Deal(DealError),
+    // Equita della distribuzione
+    InvalidEntropy { max_len: usize },
+    TooManyEntropyContributions { max: usize },
+    // Registro della partita
+    InvalidLog { seq: usize, reason: String },
+    InvalidNotation { line: usize, reason: String },
+    // Protocollo
+    InvalidPayload { action: String },
+    UnknownAction { action: String },
+}
+
+impl GameError {
+    //  Codice stabile, da non cambiare una volta pubblicato
+    pub fn code(&self) -> &'static str {
+        match self {
+            GameError::InvalidRules { .. } => "invalid_rules",
+            GameError::PlayerNotFound => "player_not_found",
+            GameError::PlayerAlreadyJoined => "player_already_joined",
+            GameError::GameAlreadyStarted => "game_already_started",
+            GameError::TableFull { .. } => "table_full",
+            GameError::SpectatorsNotAllowed => "spectators_not_allowed",
+            GameError::NotEnoughPlayers { .. } => "not_enough_players",
+            GameError::NotHost => "not_host",
+            GameError::NotABot => "not_a_bot",
+            GameError::TableClosed => "table_closed",
+            GameError::InvalidPhase { .. } => "invalid_phase",
+            GameError::InvalidTransition { .. } => "invalid_transition",
+            GameError::UnknownPausedPhase => "unknown_paused_phase",
+            GameError::NotYourTurn { .. } => "not_your_turn",
+            GameError::RoundNotOver => "round_not_over",
+            GameError::GameAlreadyOver => "game_already_over",
+            GameError::PredictionTooHigh { .. } => "prediction_too_high",
+            GameError::PredictionAlreadyMade => "prediction_already_made",
+            GameError::ForbiddenPrediction { .. } => "forbidden_prediction",
+            GameError::CardNotInHand => "card_not_in_hand",
+            GameError::MustFollowSuit { .. } => "must_follow_suit",
+            GameError::MustPlayTrump { .. } => "must_play_trump",
+            GameError::WildDeclarationRequired => "wild_declaration_required",
+            GameError::Deal(DealError::NoPlayers) => "deal_no_players",
+            GameError::Deal(DealError::NotEnoughCards { .. }) => "deal_not_enough_cards",
+            GameError::Deal(DealError::RoundOutOfRange { .. }) => "deal_round_out_of_range",
+            GameError::InvalidEntropy { .. } => "invalid_entropy",
+            GameError::TooManyEntropyContributions { .. } => "too_many_entropy_contributions",
+            GameError::InvalidLog { .. } => "invalid_log",
+            GameError::InvalidNotation { .. } => "invalid_notation",
+            GameError::InvalidPayload { .. } => "invalid_payload",
+            GameError::UnknownAction { .. } => "unknown_action",
+        }
+    }
+
+    //  Dati strutturati per permettere ai client di localizzare il messaggio
+    pub fn details(&self) -> Value {
+        match self {
+            GameError::InvalidRules { field, reason } => json!({ "field": field, "reason": reason }),
+            GameError::TableFull { max_players } => json!({ "max_players": max_players }),
+            GameError::NotEnoughPlayers { required, current } => {
+                json!({ "required": required, "current": current })
+            }
+            GameError::InvalidPhase { current, expected } => {
+                json!({ "current": current, "expected": expected })
+            }
+            GameError::InvalidTransition { from, to } => json!({ "from": from, "to": to }),
+            GameError::NotYourTurn { current_player } => json!({ "current_player": current_player }),
+            GameError::PredictionTooHigh { max } => json!({ "max": max }),
+            GameError::ForbiddenPrediction { forbidden } => json!({ "forbidden": forbidden }),
+            GameError::MustFollowSuit { suit } | GameError::MustPlayTrump { suit } => {
+                json!({ "suit": suit })
+            }
+            GameError::Deal(DealError::NotEnoughCards { needed, available }) => {
+                json!({ "needed": needed, "available": available })
+            }
+            GameError::Deal(DealError::RoundOutOfRange { round_number, starting_cards }) => {
+                json!({ "round_number": round_number, "starting_cards": starting_cards })
+            }
+            GameError::InvalidEntropy { max_len } => json!({ "max_len": max_len }),
+            GameError::TooManyEntropyContributions { max } => json!({ "max": max }),
+            GameError::InvalidLog { seq, reason } => json!({ "seq": seq, "reason": reason }),
+            GameError::InvalidNotation { line, reason } => json!({ "line": line, "reason": reason }),
+            GameError::InvalidPayload { action } | GameError::UnknownAction { action } => {
+                json!({ "action": action })
+            }
+            _ => json!({}),
+        }
+    }
+
+    //  Payload inviato ai client: {code, message, details}
+    pub fn to_json(&self) -> Value {
+        json!({
+            "code": self.code(),
+            "message": self.to_string(),
+            "details": self.details()
+        })
+    }
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::InvalidRules { reason, .. } => write!(f, "{}", reason),
+            GameError::PlayerNotFound => write!(f, "Giocatore inesistente"),
+            GameError::PlayerAlreadyJoined => write!(f, "Giocatore gia presente"),
+            GameError::GameAlreadyStarted => write!(f, "Partita gia iniziata"),
+            GameError::TableFull { .. } => write!(f, "Tavolo al completo"),
+            GameError::SpectatorsNotAllowed => write!(f, "Spettatori non ammessi a questo tavolo"),
+            GameError::NotEnoughPlayers { required, .. } => {
+                write!(f, "Servono almeno {} giocatori per iniziare", required)
+            }
+            GameError::NotHost => write!(f, "Azione riservata a chi ospita il tavolo"),
+            GameError::NotABot => write!(f, "Il giocatore indicato non e un bot"),
+            GameError::TableClosed => write!(f, "Il tavolo e stato chiuso"),
+            GameError::InvalidPhase { current, expected } => write!(
+                f,
+                "Azione non consentita nella fase {:?} (richiesta {:?})",
+                current, expected
+            ),
+            GameError::InvalidTransition { from, to } => {
+                write!(f, "Transizione non valida da {:?} a {:?}", from, to)
+            }
+            GameError::UnknownPausedPhase => write!(f, "Fase precedente sconosciuta"),
+            GameError::NotYourTurn { .. } => write!(f, "Non e il tuo turno"),
+            GameError::RoundNotOver => write!(f, "Il round corrente non e terminato"),
+            GameError::GameAlreadyOver => write!(f, "La partita e gia terminata"),
+            GameError::PredictionTooHigh { max } => {
+                write!(f, "Previsione massima consentita: {}", max)
+            }
+            GameError::PredictionAlreadyMade => write!(f, "Previsione gia fatta per questo round"),
+            GameError::ForbiddenPrediction { forbidden } => write!(
+                f,
+                "Ultimo giocatore non puo completare la somma esatta (vietato {})",
+                forbidden
+            ),
+            GameError::CardNotInHand => write!(f, "Carta non trovata nella mano del giocatore"),
+            GameError::MustFollowSuit { suit } => {
+                write!(f, "Devi rispondere al seme di uscita ({:?})", suit)
+            }
+            GameError::MustPlayTrump { suit } => write!(f, "Devi giocare briscola ({:?})", suit),
+            GameError::WildDeclarationRequired => {
+                write!(f, "Chi gioca la matta deve dichiararla alta o bassa")
+            }
+            GameError::Deal(err) => write!(f, "{}", err),
+            GameError::InvalidEntropy { max_len } => {
+                write!(f, "L'entropia deve avere tra 1 e {} caratteri", max_len)
+            }
+            GameError::TooManyEntropyContributions { .. } => {
+                write!(f, "Troppi contributi per questa distribuzione")
+            }
+            GameError::InvalidLog { seq, reason } => {
+                write!(f, "Registro non valido alla voce {}: {}", seq, reason)
+            }
+            GameError::InvalidNotation { line, reason } => {
+                write!(f, "Notazione non valida alla riga {}: {}", line, reason)
+            }
+            GameError::InvalidPayload { action } => {
+                write!(f, "Dati mancanti o non validi per l'azione {}", action)
+            }
+            GameError::UnknownAction { action } => write!(f, "Azione sconosciuta: {}", action),
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
+
+impl From<DealError> for GameError {
+    fn from(err: DealError) -> Self {
+        GameError::Deal(err)
+    }
+}
@@ -0,0 +1,205 @@
+use serde::Serialize;
+
+use super::{
+    bots::BotDifficulty,
+    card::{Card, Suit},
+    game::{GamePhase, HandView},
+    ranking::WildDeclaration,
+    rules::GameRules,
+    schedule::RoundSchedule,
+    scoring::{RoundResult, Standing},
+};
+
+// ===========================================
+// EVENTI DI DOMINIO
+// ===========================================
+//
+// Il motore non conosce il trasporto: ogni comando restituisce la lista di
+// eventi da consegnare, ciascuno con i suoi destinatari. Il livello WebSocket
+// li serializza come {event, data}, aggiungendo a `data` la fase della partita.
+
+//  Chi deve ricevere l'evento
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Audience {
+    Everyone,       // giocatori e spettatori
+    Player(String), // solo il giocatore indicato
+}
+
+//  Evento con destinatari e fase della partita al momento in cui e stato generato
+#[derive(Debug, Clone)]
+pub struct Envelope {
+    pub audience: Audience,
+    pub phase: GamePhase,
+    pub event: GameEvent,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum GameEvent {
+    Welcome(Box<TableSnapshot>),
+    PlayerJoined {
+        id: String,
+        spectator: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        bot: Option<BotDifficulty>,
+    },
+    PlayerLeft {
+        id: String,
+    },
+    GameStarted {},
+    RoundStarted(Box<RoundStart>),
+    HandUpdated {
+        player_id: String,
+        hand: Vec<Option<Card>>,
+        blind: bool,
+        other_hands: Vec<HandView>,
+    },
+    TurnChanged {
+        player_id: Option<String>,
+        dealer: Option<String>,
+        trick_leader: Option<String>,
+        time_limit: Option<u64>,
+    },
+    PredictionMade {
+        player_id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        prediction: Option<u8>, // assente a buste chiuse
+        sealed: bool,
+        auto: bool,
+    },
+    PredictionsRevealed {
+        predictions: Vec<PredictionLine>,
+    },
+    CardPlayed {
+        player_id: String,
+        card: Card,
+        declaration: Option<WildDeclaration>,
+        auto: bool,
+        blind: bool,
+    },
+    TurnEnded {
+        winner_id: String,
+        standings: Vec<TrickStanding>,
+        log_head: String,
+    },
+    RoundEnded {
+        results: Vec<RoundResult>,
+        seed_reveal: SeedReveal,
+        log_head: String,
+    },
+    GameOver {
+        standings: Vec<Standing>,
+        ranked: bool,
+        seed: u64,
+        log_head: String,
+    },
+    EntropyContributed {
+        player_id: String,
+        entropy: String,
+        next_seed_commitment: String,
+    },
+    LegalCards {
+        cards: Vec<Option<Card>>,
+    },
+    GamePaused {
+        player_id: String,
+    },
+    GameResumed {
+        player_id: String,
+    },
+    BotDifficultyChanged {
+        bot_id: String,
+        difficulty: BotDifficulty,
+    },
+}
+
+//  Stato del tavolo inviato a chi entra
+#[derive(Debug, Clone, Serialize)]
+pub struct TableSnapshot {
+    pub player_id: String,
+    pub spectator: bool,
+    pub rules: GameRules,
+    pub round_number: usize,
+    pub starting_cards: usize,
+    pub hand: Vec<Option<Card>>,
+    pub blind: bool,
+    pub other_hands: Vec<HandView>,
+    pub players: Vec<String>,
+    pub host: Option<String>,
+    pub bots: Vec<BotSeat>,
+    pub scores: Vec<ScoreLine>,
+    pub turn: Vec<PlayedCard>,
+    pub current_player: Option<String>,
+    pub dealer: Option<String>,
+    pub trump_card: Option<Card>,
+    pub trump_suit: Option<Suit>,
+    pub seed_commitment: Option<String>,
+    pub next_seed_commitment: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RoundStart {
+    pub round_number: usize,
+    pub starting_cards: usize,
+    pub cards_per_player: usize,
+    pub total_rounds: Option<usize>, // assente nella modalita a vite
+    pub round_schedule: RoundSchedule,
+    pub dealer: Option<String>,
+    pub trump_card: Option<Card>,
+    pub trump_suit: Option<Suit>,
+    pub lives: Vec<LivesLine>,
+    pub seed_commitment: String,
+    pub entropy: Vec<String>,
+    pub next_seed_commitment: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoreLine {
+    pub player_id: String,
+    pub score: i32,
+    pub lives: i32,
+    pub eliminated: bool,
+}
+
+//  Bot seduto al tavolo e il suo livello
+#[derive(Debug, Clone, Serialize)]
+pub struct BotSeat {
+    pub player_id: String,
+    pub difficulty: BotDifficulty,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LivesLine {
+    pub player_id: String,
+    pub lives: i32,
+    pub eliminated: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlayedCard {
+    pub player_id: String,
+    pub card: Card,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PredictionLine {
+    pub player_id: String,
+    pub prediction: Option<u8>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrickStanding {
+    pub player_id: String,
+    pub tricks_won: u8,
+    pub score: i32,
+    pub lives: i32,
+}
+
+//  Seed rivelato a fine round per la verifica della distribuzione
+#[derive(Debug, Clone, Serialize)]
+pub struct SeedReveal {
+    pub round_seed: u64,
+    pub seed_commitment: String,
+    pub entropy: Vec<String>,
+    pub deck_seed: u64,
+}
@@ -0,0 +1,272 @@
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::time::{Duration, Instant};
+
+use super::{
+    bots::{Bot, BotView, EasyBot},
+    card::{Card, Suit},
+    game::{GamePhase, GameState},
+    log::GameLog,
+    ranking::WildDeclaration,
+};
+
+// ===========================================
+// BOT ESPERTO (ricerca Monte Carlo)
+// ===========================================
+//
+// Per ogni decisione il bot immagina molte distribuzioni delle carte che non
+// vede, compatibili con quanto osservato nel round: carte gia giocate e semi
+// mancanti rivelati da chi non ha risposto al seme di uscita. Su ciascuna
+// gioca il resto del round nel motore (`GameState`) e sceglie l'azione con il
+// punteggio medio migliore, fino a esaurire il tempo a disposizione.
+
+//  Tentativi di distribuzione rispettando i semi mancanti, poi si ignorano
+const SAMPLE_ATTEMPTS: usize = 20;
+//  Simulazioni massime per decisione, anche se avanza tempo
+const MAX_SAMPLES: usize = 2_000;
+
+pub struct ExpertBot {
+    pub budget: Duration, // tempo di riflessione per ogni decisione
+}
+
+impl Bot for ExpertBot {
+    fn prediction(&self, view: &BotView) -> u8 {
+        let candidates: Vec<u8> = (0..=view.hand().len() as u8)
+            .filter(|&value| view.game.validate_prediction(view.player_id, value).is_ok())
+            .collect();
+        let sampler = Sampler::new(view);
+        let best = sampler.search(candidates.len(), self.budget, |world, index| {
+            complete_predictions(world, view.player_id, candidates[index]);
+            playout(world);
+            utility(world, view.player_id)
+        });
+        candidates.get(best).copied().unwrap_or(0)
+    }
+
+    fn card(&self, view: &BotView, legal: &[Card]) -> (Card, WildDeclaration) {
+        // La matta si prova sia alta sia bassa
+        let mut candidates: Vec<(Card, WildDeclaration)> = Vec::new();
+        for card in legal {
+            candidates.push((card.clone(), WildDeclaration::High));
+            if view.game.rules.is_wild(card) {
+                candidates.push((card.clone(), WildDeclaration::Low));
+            }
+        }
+        let sampler = Sampler::new(view);
+        let best = sampler.search(candidates.len(), self.budget, |world, index| {
+            let (card, declaration) = &candidates[index];
+            if world.play_card(view.player_id, card.clone(), Some(*declaration)).is_err() {
+                return i32::MIN / 2;
+            }
+            playout(world);
+            utility(world, view.player_id)
+        });
+        candidates.swap_remove(best.min(candidates.len() - 1))
+    }
+}
+
+// ===========================================
+// CAMPIONAMENTO DELLE MANI NASCOSTE
+// ===========================================
+
+struct Sampler {
+    base: GameState,            // stato visto dal bot, senza registro
+    hidden: Vec<(usize, usize)>, // (posto, carte in mano) dei giocatori da indovinare
+    pool: Vec<Card>,            // carte da distribuire tra loro e il mazzo
+    voids: Vec<(String, Suit)>, // semi che un giocatore ha dimostrato di non avere
+}
+
+impl Sampler {
+    fn new(view: &BotView) -> Self {
+        let game = view.game;
+        let mut base = game.clone();
+        base.log = GameLog::default();
+
+        // A buste chiuse le previsioni altrui non sono ancora note
+        if base.phase == GamePhase::Predicting && base.rules.sealed_predictions() {
+            for player in base.players.iter_mut().filter(|p| p.id != view.player_id) {
+                player.prediction = None;
+            }
+        }
+
+        let visible: Vec<String> = game
+            .hand_views(view.player_id)
+            .into_iter()
+            .filter(|v| v.cards.iter().all(Option::is_some))
+            .map(|v| v.player_id)
+            .collect();
+        let voids = view.observed_voids();
+        let mut hidden: Vec<(usize, usize)> = game
+            .players
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| !p.hand.is_empty() && !visible.contains(&p.id))
+            .map(|(index, p)| (index, p.hand.len()))
+            .collect();
+        // Prima chi ha piu vincoli, cosi la distribuzione riesce piu spesso
+        hidden.sort_by_key(|(index, _)| {
+            std::cmp::Reverse(voids.iter().filter(|(id, _)| *id == game.players[*index].id).count())
+        });
+
+        Self { base, hidden, pool: view.unseen(), voids }
+    }
+
+    //  Una distribuzione possibile delle carte nascoste
+    fn sample(&self, rng: &mut ChaCha8Rng) -> GameState {
+        let mut world = self.base.clone();
+        for attempt in 0..=SAMPLE_ATTEMPTS {
+            let mut pool = self.pool.clone();
+            pool.shuffle(rng);
+            world = self.base.clone();
+            if self.deal(&mut world, &mut pool, attempt < SAMPLE_ATTEMPTS) {
+                world.deck = pool;
+                break;
+            }
+        }
+        world
+    }
+
+    fn deal(&self, world: &mut GameState, pool: &mut Vec<Card>, respect_voids: bool) -> bool {
+        let rules = &self.base.rules;
+        for &(index, count) in &self.hidden {
+            let id = &self.base.players[index].id;
+            let allowed = |card: &Card| {
+                !respect_voids
+                    || rules.is_wild(card)
+                    || !self.voids.iter().any(|(player, suit)| player == id && *suit == card.suit)
+            };
+            let mut hand = Vec::with_capacity(count);
+            let mut i = 0;
+            while hand.len() < count && i < pool.len() {
+                if allowed(&pool[i]) {
+                    hand.push(pool.swap_remove(i));
+                } else {
+                    i += 1;
+                }
+            }
+            if hand.len() < count {
+                return false;
+            }
+            world.players[index].hand = hand;
+        }
+        true
+    }
+
+    //  Valuta ogni candidata sulle stesse distribuzioni finche c'e tempo;
+    //  restituisce l'indice della candidata con il punteggio totale migliore
+    fn search(&self, candidates: usize, budget: Duration, evaluate: impl Fn(&mut GameState, usize) -> i32) -> usize {
+        if candidates <= 1 {
+            return 0;
+        }
+        let started = Instant::now();
+        let mut rng = ChaCha8Rng::from_entropy();
+        let mut totals = vec![0i64; candidates];
+        let mut samples = 0;
+        while samples < MAX_SAMPLES && (samples == 0 || started.elapsed() < budget) {
+            let world = self.sample(&mut rng);
+            for (index, total) in totals.iter_mut().enumerate() {
+                let mut simulation = world.clone();
+                *total += evaluate(&mut simulation, index) as i64;
+            }
+            samples += 1;
+        }
+        // A parita vince la prima candidata
+        totals
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(&a.0)))
+            .map_or(0, |(index, _)| index)
+    }
+}
+
+// ===========================================
+// SIMULAZIONE DEL ROUND
+// ===========================================
+
+//  Fissa la previsione del bot e stima quelle mancanti degli altri
+fn complete_predictions(world: &mut GameState, player_id: &str, prediction: u8) {
+    let estimates: Vec<(usize, u8)> = world
+        .players
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.is_active() && p.prediction.is_none() && p.id != player_id)
+        .map(|(index, p)| (index, EasyBot.prediction(&BotView { game: world, player_id: &p.id })))
+        .collect();
+    for (index, estimate) in estimates {
+        world.players[index].prediction = Some(estimate);
+    }
+    if let Some(me) = world.players.iter_mut().find(|p| p.id == player_id) {
+        me.prediction = Some(prediction);
+    }
+    if world.phase == GamePhase::Predicting && world.transition(GamePhase::Playing).is_ok() {
+        world.current_player = world.trick_leader;
+    }
+}
+
+//  Gioca il resto del round con una politica rapida per tutti i giocatori
+fn playout(world: &mut GameState) {
+    while world.phase == GamePhase::Playing {
+        if world.current_turn_cards.len() == world.active_player_count() {
+            world.end_turn();
+            continue;
+        }
+        if world.is_round_over() {
+            break;
+        }
+        let Some(player_id) = world.current_player_id().map(str::to_string) else {
+            break;
+        };
+        let legal = world.legal_cards(&player_id).unwrap_or_default();
+        if legal.is_empty() {
+            break;
+        }
+        let (card, declaration) = rollout_card(world, &player_id, &legal);
+        if world.play_card(&player_id, card, Some(declaration)).is_err() {
+            break;
+        }
+    }
+}
+
+//  Chi deve prendere usa la carta minima che prende (apre con la piu forte),
+//  chi non deve prendere scarta la piu forte che non prende
+fn rollout_card(world: &GameState, player_id: &str, legal: &[Card]) -> (Card, WildDeclaration) {
+    let view = BotView { game: world, player_id };
+    let by_strength = |a: &&Card, b: &&Card| view.strength(a).cmp(&view.strength(b));
+    let taking = |card: &Card| match world.leading_suit.as_ref() {
+        Some(leading) => match view.trick_best(leading) {
+            Some(best) => world.card_beats(card, best, leading),
+            None => true,
+        },
+        None => true,
+    };
+    let weakest = || legal.iter().min_by(by_strength);
+
+    let (card, declaration) = if view.tricks_needed() > 0 {
+        let card = match world.leading_suit {
+            None => legal.iter().max_by(by_strength),
+            Some(_) => legal.iter().filter(|c| taking(c)).min_by(by_strength).or_else(weakest),
+        };
+        (card, WildDeclaration::High)
+    } else {
+        let card = legal.iter().filter(|c| !taking(c)).max_by(by_strength).or_else(weakest);
+        (card, WildDeclaration::Low)
+    };
+    (card.unwrap_or(&legal[0]).clone(), declaration)
+}
+
+//  Esito del round per il bot: punti del round, oppure vite perse nella modalita a vite
+fn utility(world: &GameState, player_id: &str) -> i32 {
+    let Some(player) = world.players.iter().find(|p| p.id == player_id) else {
+        return 0;
+    };
+    if world.rules.uses_lives() {
+        -(player.prediction.unwrap_or(0) as i32 - player.tricks_won as i32).abs()
+    } else {
+        world
+            .rules
+            .scoring_system
+            .score(player.prediction, player.tricks_won)
+            .total()
+    }
+}
@@ -0,0 +1,35 @@
+use sha2::{Digest, Sha256};
+
+// ===========================================
+// COMMIT-REVEAL DEL MAZZO
+// ===========================================
+//
+// Prima di distribuire il server pubblica `seed_commitment(round_seed)`.
+// I giocatori possono aggiungere entropia, che viene mescolata al seed con
+// `deck_seed`. A fine round il seed viene rivelato: chiunque puo verificare
+// che l'hash coincida e rigenerare il mazzo con `Card::shuffle_deck(deck_seed, decks)`.
+
+// Lunghezza massima di un contributo di entropia
+pub const MAX_ENTROPY_LEN: usize = 64;
+
+// Numero massimo di contributi per distribuzione
+pub const MAX_ENTROPY_CONTRIBUTIONS: usize = 16;
+
+//  Hash (esadecimale) pubblicato prima della distribuzione
+pub fn seed_commitment(round_seed: u64) -> String {
+    hex::encode(Sha256::digest(round_seed.to_be_bytes()))
+}
+
+//  Seed effettivo del mazzo: seed del server piu i contributi dei giocatori, in ordine
+pub fn deck_seed(round_seed: u64, entropy: &[String]) -> u64 {
+    let mut hasher = Sha256::new();
+    hasher.update(round_seed.to_be_bytes());
+    for contribution in entropy {
+        hasher.update((contribution.len() as u32).to_be_bytes());
+        hasher.update(contribution.as_bytes());
+    }
+    let digest = hasher.finalize();
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    u64::from_be_bytes(bytes)
+}
@@ -1,89 +1,320 @@
-use axum::extract::ws::Message;
-use serde_json::Value;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
 
 use super::{
-    card::{deal_round, Card, Suit},
+    card::{deal_round, random_seed, round_seed, Card, DealError, Suit},
+    error::GameError,
+    fairness::{deck_seed, seed_commitment, MAX_ENTROPY_CONTRIBUTIONS, MAX_ENTROPY_LEN},
+    log::{GameLog, LogEntry},
     player::Player,
+    ranking::WildDeclaration,
+    rules::GameRules,
+    scoring::{rank_players, RoundResult, Standing},
 };
 
-#[derive(Debug)]
+//  Fasi della partita (coincidono con game_status in database/init.js)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum GamePhase {
+    Waiting,
+    Predicting,
+    Playing,
+    Paused,
+    Finished,
+}
+
+impl GamePhase {
+    //  Una partita finita non riparte: la rivincita si gioca su un nuovo
+    //  tavolo, con un nuovo seed (quello vecchio e gia stato rivelato)
+    pub fn can_transition_to(self, next: GamePhase) -> bool {
+        use GamePhase::*;
+        matches!(
+            (self, next),
+            (Waiting, Predicting)
+                | (Predicting, Playing)
+                | (Predicting, Paused)
+                | (Playing, Predicting)
+                | (Playing, Paused)
+                | (Playing, Finished)
+                | (Paused, Predicting)
+                | (Paused, Playing)
+        )
+    }
+}
+
+//  Mano come la vede un giocatore: le carte coperte sono `None`
+#[derive(Debug, Clone, Serialize)]
+pub struct HandView {
+    pub player_id: String,
+    pub cards: Vec<Option<Card>>,
+}
+
+#[derive(Debug, Clone)]
 pub struct GameState {
+    pub seed: u64, // seed della partita, da cui deriva ogni mescolata
+    pub pending_entropy: Vec<String>, // contributi dei giocatori per la prossima distribuzione
+    pub round_entropy: Vec<String>,   // contributi usati nella distribuzione corrente
+    pub rules: GameRules,
     pub players: Vec<Player>,
+    pub spectators: Vec<Player>,
     pub round_number: usize,
     pub starting_cards: usize,
     pub deck: Vec<Card>,
     pub current_turn_cards: Vec<(String, Card)>, // (player_id, card)
+    pub wild_declarations: Vec<(String, WildDeclaration)>, // matte giocate nella mano corrente
     pub leading_suit: Option<Suit>,
+    pub trump_card: Option<Card>, // briscola scoperta del round (regola show_trump_card)
+    pub dealer: usize,         // indice del mazziere, ruota ogni round
+    pub current_player: usize, // indice del giocatore che deve agire
+    pub trick_leader: usize,   // chi apre la mano (vincitore della mano precedente)
+    pub phase: GamePhase,
+    pub paused_from: Option<GamePhase>, // fase da ripristinare alla ripresa
+    pub turn_started_at: Instant,       // inizio del turno corrente, per i tempi limite
+    pub round_results: Vec<RoundResult>, // risultati dell'ultimo round concluso
+    pub log: GameLog,                    // registro ordinato di tutto cio che e accaduto
 }
 
 impl GameState {
-    pub fn new_game(num_players: usize, starting_cards: usize) -> Self {
-        GameState {
-            players: Vec::with_capacity(num_players),
+    //  Crea un tavolo con le regole indicate, dopo averle validate
+    pub fn new_game(rules: GameRules) -> Result<Self, GameError> {
+        Self::with_seed(rules, random_seed())
+    }
+
+    //  Crea un tavolo riproducibile: tutti i mazzi derivano dal seed
+    pub fn with_seed(rules: GameRules, seed: u64) -> Result<Self, GameError> {
+        rules.validate()?;
+        let mut log = GameLog::default();
+        log.append(LogEntry::Created { rules: rules.clone(), seed });
+        Ok(GameState {
+            seed,
+            pending_entropy: Vec::new(),
+            round_entropy: Vec::new(),
+            players: Vec::with_capacity(rules.max_players),
+            spectators: Vec::new(),
             round_number: 1,
-            starting_cards,
-            deck: Card::shuffle_deck(),
+            starting_cards: rules.starting_cards(rules.max_players)?,
+            deck: Card::shuffle_deck(round_seed(seed, 1), rules.deck_count()),
             current_turn_cards: Vec::new(),
+            wild_declarations: Vec::new(),
             leading_suit: None,
-        }
+            trump_card: None,
+            dealer: 0,
+            current_player: 0,
+            trick_leader: 0,
+            phase: GamePhase::Waiting,
+            paused_from: None,
+            turn_started_at: Instant::now(),
+            round_results: Vec::new(),
+            log,
+            rules,
+        })
     }
 
-    pub fn start_game(&mut self) {
+    pub fn start_game(&mut self) -> Result<(), GameError> {
+        if self.players.len() < self.rules.min_players {
+            return Err(GameError::NotEnoughPlayers {
+                required: self.rules.min_players,
+                current: self.players.len(),
+            });
+        }
+        // Verifica la mano iniziale prima di cambiare fase
+        let starting_cards = self.rules.starting_cards(self.players.len())?;
+        self.transition(GamePhase::Predicting)?;
+        self.starting_cards = starting_cards;
         self.round_number = 1;
-        self.deck = Card::shuffle_deck();
-        self.deal_round();
+        self.dealer = 0;
+        self.round_results.clear();
+        let starting_lives = self.rules.victory_conditions.starting_lives;
+        for player in self.players.iter_mut() {
+            player.score = 0;
+            player.lives = starting_lives;
+            player.eliminated_in = None;
+        }
+        self.shuffle_for_round();
+        Ok(self.deal_round()?)
     }
 
-    pub fn add_player(&mut self, player: Player) -> Result<(), String> {
+    //  Cambia fase solo se la transizione e ammessa
+    pub fn transition(&mut self, next: GamePhase) -> Result<(), GameError> {
+        if !self.phase.can_transition_to(next) {
+            return Err(GameError::InvalidTransition { from: self.phase, to: next });
+        }
+        self.phase = next;
+        Ok(())
+    }
+
+    pub fn require_phase(&self, expected: GamePhase) -> Result<(), GameError> {
+        if self.phase != expected {
+            return Err(GameError::InvalidPhase { current: self.phase, expected });
+        }
+        Ok(())
+    }
+
+    pub fn pause(&mut self) -> Result<(), GameError> {
+        let current = self.phase;
+        self.transition(GamePhase::Paused)?;
+        self.paused_from = Some(current);
+        Ok(())
+    }
+
+    pub fn resume(&mut self) -> Result<(), GameError> {
+        self.require_phase(GamePhase::Paused)?;
+        let previous = self
+            .paused_from
+            .take()
+            .ok_or(GameError::UnknownPausedPhase)?;
+        self.transition(previous)?;
+        self.turn_started_at = Instant::now();
+        Ok(())
+    }
+
+    //  C'e ancora un posto libero al tavolo?
+    pub fn seat_available(&self) -> bool {
+        self.phase == GamePhase::Waiting && self.players.len() < self.rules.max_players
+    }
+
+    //  Il tavolo e pieno e le regole prevedono l'avvio automatico
+    pub fn should_auto_start(&self) -> bool {
+        self.rules.auto_start
+            && self.phase == GamePhase::Waiting
+            && self.players.len() == self.rules.max_players
+    }
+
+    pub fn add_player(&mut self, player: Player) -> Result<(), GameError> {
+        if self.phase != GamePhase::Waiting {
+            return Err(GameError::GameAlreadyStarted);
+        }
+        if self.players.len() >= self.rules.max_players {
+            return Err(GameError::TableFull { max_players: self.rules.max_players });
+        }
         if self.players.iter().any(|p| p.id == player.id) {
-            return Err("Giocatore gia presente".to_string());
+            return Err(GameError::PlayerAlreadyJoined);
         }
         self.players.push(player);
         Ok(())
     }
 
+    pub fn add_spectator(&mut self, spectator: Player) -> Result<(), GameError> {
+        if !self.rules.allow_spectators {
+            return Err(GameError::SpectatorsNotAllowed);
+        }
+        self.spectators.push(spectator);
+        Ok(())
+    }
+
     pub fn remove_player(&mut self, player_id: &str) {
-        self.players.retain(|p| p.id != player_id);
+        self.spectators.retain(|s| s.id != player_id);
+        let Some(index) = self.player_index(player_id) else {
+            return;
+        };
+        self.players.remove(index);
         self.current_turn_cards
             .retain(|(id, _)| id != player_id);
-    }
+        self.wild_declarations.retain(|(id, _)| id != player_id);
 
-    pub fn broadcast(&self, event: &str, data: Value) {
-        if self.players.is_empty() {
-            return;
-        }
-        let payload = serde_json::json!({ "event": event, "data": data }).to_string();
-        for player in &self.players {
-            let _ = player.sender.send(Message::Text(payload.clone()));
+        // Riallinea i puntatori ai posti rimasti
+        let remaining = self.players.len();
+        for seat in [&mut self.dealer, &mut self.current_player, &mut self.trick_leader] {
+            if *seat > index {
+                *seat -= 1;
+            }
+            if *seat >= remaining {
+                *seat = 0;
+            }
         }
     }
 
-    pub fn deal_round(&mut self) {
-        if self.players.is_empty() {
-            return;
-        }
+    //  Distribuisce solo ai giocatori ancora in gioco
+    pub fn deal_round(&mut self) -> Result<(), DealError> {
+        let active_players = self.active_player_count();
+        let schedule_round = self.schedule_round();
         let hands = deal_round(
             &mut self.deck,
-            self.players.len(),
-            self.round_number,
+            active_players,
+            schedule_round,
             self.starting_cards,
-        );
-        for (player, hand) in self.players.iter_mut().zip(hands) {
-            player.hand = hand;
+            &self.rules.round_schedule,
+        )?;
+        let mut hands = hands.into_iter();
+        for player in self.players.iter_mut() {
+            player.hand = if player.is_active() {
+                hands.next().unwrap_or_default()
+            } else {
+                Vec::new()
+            };
             player.prediction = None;
             player.tricks_won = 0;
         }
         self.current_turn_cards.clear();
+        self.wild_declarations.clear();
         self.leading_suit = None;
+
+        // La briscola e la carta in cima al mazzo rimasto; se e finito il round
+        // si gioca con la gerarchia dei semi del tavolo
+        self.trump_card = if self.rules.show_trump_card {
+            self.deck.pop()
+        } else {
+            None
+        };
+
+        // Parla per primo il giocatore alla sinistra del mazziere
+        let first = self.next_seat(self.dealer);
+        self.set_turn(first);
+        self.trick_leader = first;
+
+        // Senza previsioni obbligatorie si passa subito al gioco
+        if !self.rules.prediction_required && self.phase == GamePhase::Predicting {
+            let _ = self.transition(GamePhase::Playing);
+        }
+        Ok(())
     }
 
-    pub fn make_prediction(&mut self, player_id: &str, prediction: u8) -> Result<(), String> {
+    pub fn make_prediction(&mut self, player_id: &str, prediction: u8) -> Result<(), GameError> {
+        self.validate_prediction(player_id, prediction)?;
         let player_index = self
             .player_index(player_id)
-            .ok_or_else(|| "Giocatore inesistente".to_string())?;
+            .ok_or(GameError::PlayerNotFound)?;
+        let sealed = self.rules.sealed_predictions();
+
+        self.players[player_index].prediction = Some(prediction);
 
-        if self.players.is_empty() {
-            return Err("Nessun giocatore in partita".to_string());
+        // Dopo l'ultima previsione tocca a chi apre la prima mano
+        if self.predictions_complete() {
+            self.set_turn(self.trick_leader);
+            self.transition(GamePhase::Playing)?;
+        } else if !sealed {
+            self.set_turn(self.next_seat(player_index));
+        }
+        Ok(())
+    }
+
+    //  Controlla la previsione senza applicarla (usato anche dai bot)
+    pub fn validate_prediction(&self, player_id: &str, prediction: u8) -> Result<(), GameError> {
+        let player_index = self
+            .player_index(player_id)
+            .ok_or(GameError::PlayerNotFound)?;
+
+        self.require_phase(GamePhase::Predicting)?;
+        let sealed = self.rules.sealed_predictions();
+        // A buste chiuse si parla in qualsiasi ordine
+        if !sealed && player_index != self.current_player {
+            return Err(self.not_your_turn());
+        }
+        let player = &self.players[player_index];
+        if !player.is_active() {
+            return Err(self.not_your_turn());
+        }
+        if player.prediction.is_some() {
+            return Err(GameError::PredictionAlreadyMade);
+        }
+
+        // Non si possono chiamare piu prese delle carte in mano
+        let cards_in_hand = player.hand.len() as u8;
+        let max = self
+            .rules
+            .max_predictions
+            .map_or(cards_in_hand, |limit| limit.min(cards_in_hand));
+        if prediction > max {
+            return Err(GameError::PredictionTooHigh { max });
         }
 
         let predictions_so_far: Vec<u8> = self
@@ -91,95 +322,635 @@ impl GameState {
             .iter()
             .filter_map(|p| p.prediction)
             .collect();
-        let cards_in_hand = self.players[player_index].hand.len() as u8;
 
-        if predictions_so_far.len() + 1 == self.players.len() {
+        if !sealed && self.rules.hook_rule && predictions_so_far.len() + 1 == self.active_player_count() {
             let total: u8 = predictions_so_far.iter().copied().sum();
             if total + prediction == cards_in_hand {
-                return Err("Ultimo giocatore non puo completare la somma esatta".to_string());
+                return Err(GameError::ForbiddenPrediction { forbidden: prediction });
             }
         }
-
-        self.players[player_index].prediction = Some(prediction);
         Ok(())
     }
 
-    pub fn play_card(&mut self, player_id: &str, card: Card) -> Result<(), String> {
+    //  Tutti i giocatori in gioco hanno fatto la loro previsione
+    pub fn predictions_complete(&self) -> bool {
+        self.players
+            .iter()
+            .filter(|p| p.is_active())
+            .all(|p| p.prediction.is_some())
+    }
+
+    //  `declaration` serve solo per la matta, per le altre carte viene ignorata
+    pub fn play_card(
+        &mut self,
+        player_id: &str,
+        card: Card,
+        declaration: Option<WildDeclaration>,
+    ) -> Result<(), GameError> {
         let player_index = self
             .player_index(player_id)
-            .ok_or_else(|| "Giocatore inesistente".to_string())?;
+            .ok_or(GameError::PlayerNotFound)?;
 
-        let player = &mut self.players[player_index];
+        self.require_phase(GamePhase::Playing)?;
+        // Una sola carta per mano, anche se i posti sono cambiati nel frattempo
+        if player_index != self.current_player
+            || self.current_turn_cards.iter().any(|(id, _)| id == player_id)
+        {
+            return Err(self.not_your_turn());
+        }
+
+        let player = &self.players[player_index];
         let card_position = player
             .hand
             .iter()
             .position(|c| c == &card)
-            .ok_or_else(|| "Carta non trovata nella mano del giocatore".to_string())?;
+            .ok_or(GameError::CardNotInHand)?;
 
+        if !self.playable_cards(&player.hand).contains(&card) {
+            return Err(self.illegal_card_error(&player.hand));
+        }
+        let wild = self.rules.is_wild(&card);
+        if wild && declaration.is_none() {
+            return Err(GameError::WildDeclarationRequired);
+        }
+
+        let player = &mut self.players[player_index];
         let played_card = player.hand.remove(card_position);
 
-        if self.leading_suit.is_none() {
+        // La matta non conta per il seme: lo fissa la prima carta non matta
+        if self.leading_suit.is_none() && !wild {
             self.leading_suit = Some(played_card.suit.clone());
         }
 
+        if let Some(declaration) = declaration.filter(|_| wild) {
+            self.wild_declarations.push((player.id.clone(), declaration));
+        }
         self.current_turn_cards
             .push((player.id.clone(), played_card));
+        self.set_turn(self.next_seat(player_index));
         Ok(())
     }
 
+    //  Carte che il giocatore puo giocare adesso (vuoto se non e il suo turno)
+    pub fn legal_cards(&self, player_id: &str) -> Result<Vec<Card>, GameError> {
+        let player_index = self
+            .player_index(player_id)
+            .ok_or(GameError::PlayerNotFound)?;
+
+        if self.phase != GamePhase::Playing || player_index != self.current_player {
+            return Ok(Vec::new());
+        }
+        Ok(self.playable_cards(&self.players[player_index].hand))
+    }
+
+    //  Obbligo di risposta al seme: seme di uscita, altrimenti briscola se richiesto
+    //  La matta non conta per il seme e si puo giocare in qualsiasi momento.
+    fn playable_cards(&self, hand: &[Card]) -> Vec<Card> {
+        let Some(leading) = self.leading_suit.as_ref() else {
+            return hand.to_vec();
+        };
+        let has_suit = |suit: &Suit| hand.iter().any(|c| &c.suit == suit && !self.rules.is_wild(c));
+
+        let forced = if has_suit(leading) {
+            Some(leading.clone())
+        } else {
+            self.trump_suit()
+                .filter(|trump| self.rules.must_trump_if_void && has_suit(trump))
+        };
+        match forced {
+            Some(suit) => hand
+                .iter()
+                .filter(|c| c.suit == suit || self.rules.is_wild(c))
+                .cloned()
+                .collect(),
+            None => hand.to_vec(),
+        }
+    }
+
+    //  Motivo per cui una carta non e giocabile
+    fn illegal_card_error(&self, hand: &[Card]) -> GameError {
+        match self.leading_suit.as_ref() {
+            Some(leading) if hand.iter().any(|c| &c.suit == leading && !self.rules.is_wild(c)) => {
+                GameError::MustFollowSuit { suit: leading.clone() }
+            }
+            Some(leading) => match self.trump_suit() {
+                Some(suit) => GameError::MustPlayTrump { suit },
+                None => GameError::MustFollowSuit { suit: leading.clone() },
+            },
+            None => GameError::CardNotInHand,
+        }
+    }
+
+    //  Dichiarazione della matta giocata dal giocatore nella mano corrente
+    pub fn wild_declaration(&self, player_id: &str) -> Option<WildDeclaration> {
+        self.wild_declarations
+            .iter()
+            .find(|(id, _)| id == player_id)
+            .map(|(_, declaration)| *declaration)
+    }
+
+    fn not_your_turn(&self) -> GameError {
+        GameError::NotYourTurn {
+            current_player: self.current_player_id().map(str::to_string),
+        }
+    }
+
+    //  Seme dominante: quello della briscola scoperta, altrimenti il piu forte
+    //  nella gerarchia del tavolo
+    pub fn trump_suit(&self) -> Option<Suit> {
+        match self.trump_card.as_ref() {
+            Some(card) => Some(card.suit.clone()),
+            None => self.rules.card_ranking.strongest_suit(),
+        }
+    }
+
+    //  `challenger` batte `best` con il seme di uscita indicato (matta esclusa)
+    pub fn card_beats(&self, challenger: &Card, best: &Card, leading: &Suit) -> bool {
+        let trump = self.trump_card.as_ref().map(|card| &card.suit);
+        challenger.beats_custom(best, leading, trump, &self.rules.card_ranking)
+    }
+
     pub fn end_turn(&mut self) -> Option<String> {
-        if self.current_turn_cards.is_empty() {
+        if self.phase != GamePhase::Playing || self.current_turn_cards.is_empty() {
             return None;
         }
-        let leading = match self.leading_suit.as_ref() {
-            Some(leading) => leading,
-            None => return None,
-        };
+        // Senza seme di uscita la mano e fatta solo di matte
+        let leading = self.leading_suit.clone();
 
         let mut best_index = 0;
         for i in 1..self.current_turn_cards.len() {
-            let (_, ref card_i) = self.current_turn_cards[i];
-            let (_, ref card_best) = self.current_turn_cards[best_index];
-            if card_i.beats_custom(card_best, leading) {
+            let (ref id_i, ref card_i) = self.current_turn_cards[i];
+            let (ref id_best, ref card_best) = self.current_turn_cards[best_index];
+            // La matta alta batte tutto, quella bassa perde con tutto;
+            // a parita di dichiarazione resta la prima giocata.
+            // Con due mazzi due carte identiche non si battono: vince la prima giocata.
+            let beats = match (self.wild_declaration(id_i), self.wild_declaration(id_best)) {
+                (Some(WildDeclaration::High), Some(WildDeclaration::High)) => false,
+                (Some(WildDeclaration::High), _) => true,
+                (_, Some(WildDeclaration::High)) => false,
+                (Some(WildDeclaration::Low), _) => false,
+                (_, Some(WildDeclaration::Low)) => true,
+                (None, None) => leading
+                    .as_ref()
+                    .is_some_and(|leading| self.card_beats(card_i, card_best, leading)),
+            };
+            if beats {
                 best_index = i;
             }
         }
 
         let winner_id = self.current_turn_cards[best_index].0.clone();
-        if let Some(player) = self.players.iter_mut().find(|p| p.id == winner_id) {
-            player.tricks_won += 1;
+        if let Some(winner_index) = self.player_index(&winner_id) {
+            self.players[winner_index].tricks_won += 1;
+            // Il vincitore apre la mano successiva
+            self.trick_leader = winner_index;
+            self.set_turn(winner_index);
         }
 
         self.current_turn_cards.clear();
+        self.wild_declarations.clear();
         self.leading_suit = None;
+
+        if self.is_round_over() {
+            self.score_round();
+            if self.is_game_over() {
+                let _ = self.transition(GamePhase::Finished);
+            }
+        }
         Some(winner_id)
     }
 
-    pub fn end_round(&self) -> Vec<(String, bool)> {
-        self.players
+    //  Risultati dell'ultimo round concluso
+    pub fn end_round(&self) -> &[RoundResult] {
+        &self.round_results
+    }
+
+    //  Classifica finale della partita. A vite conta prima chi e sopravvissuto
+    //  piu a lungo, poi le vite rimaste.
+    pub fn final_standings(&self) -> Vec<Standing> {
+        if self.rules.uses_lives() {
+            let entries: Vec<(String, i32, (usize, i32))> = self
+                .players
+                .iter()
+                .map(|p| {
+                    let survived = p.eliminated_in.unwrap_or(usize::MAX);
+                    (p.id.clone(), p.lives, (survived, p.lives))
+                })
+                .collect();
+            return rank_players(&entries);
+        }
+        let entries: Vec<(String, i32, i32)> = self
+            .players
             .iter()
+            .map(|p| (p.id.clone(), p.score, p.score))
+            .collect();
+        rank_players(&entries)
+    }
+
+    //  Assegna i punti del round e aggiorna i punteggi cumulativi
+    fn score_round(&mut self) {
+        let scoring = &self.rules.scoring_system;
+        let uses_lives = self.rules.uses_lives();
+        let round_number = self.round_number;
+        self.round_results = self
+            .players
+            .iter_mut()
+            .filter(|p| p.is_active())
             .map(|p| {
-                let success = p.prediction.unwrap_or(0) == p.tricks_won;
-                (p.id.clone(), success)
+                let breakdown = scoring.score(p.prediction, p.tricks_won);
+                let points = breakdown.total();
+                p.score += points;
+
+                // A vite ogni presa di scarto costa una vita
+                if uses_lives {
+                    let miss = (p.prediction.unwrap_or(0) as i32 - p.tricks_won as i32).abs();
+                    p.lives -= miss;
+                    if p.lives <= 0 {
+                        p.eliminated_in = Some(round_number);
+                    }
+                }
+                RoundResult {
+                    player_id: p.id.clone(),
+                    prediction: p.prediction,
+                    tricks_won: p.tricks_won,
+                    success: p.prediction == Some(p.tricks_won),
+                    points,
+                    breakdown,
+                    total_score: p.score,
+                    lives: uses_lives.then_some(p.lives),
+                    eliminated: !p.is_active(),
+                }
             })
-            .collect()
+            .collect();
     }
 
-    pub fn next_round(&mut self) {
+    pub fn next_round(&mut self) -> Result<(), GameError> {
+        self.require_phase(GamePhase::Playing)?;
+        if !self.is_round_over() {
+            return Err(GameError::RoundNotOver);
+        }
+        if self.is_game_over() {
+            return Err(GameError::GameAlreadyOver);
+        }
+        self.transition(GamePhase::Predicting)?;
         self.round_number += 1;
-        self.deck = Card::shuffle_deck();
-        self.deal_round();
+        self.dealer = self.next_seat(self.dealer);
+        self.shuffle_for_round();
+        Ok(self.deal_round()?)
     }
 
     pub fn is_round_over(&self) -> bool {
         self.players.iter().all(|p| p.hand.is_empty())
     }
 
+    //  Chiamata a round appena concluso, prima che `next_round` incrementi il
+    //  numero (che parte da 1): l'ultimo round giocato e `total_rounds()`
     pub fn is_game_over(&self) -> bool {
-        self.round_number > self.starting_cards
+        if self.rules.uses_lives() {
+            return self.active_player_count() <= 1;
+        }
+        self.round_number >= self.total_rounds()
+    }
+
+    //  Numero di round previsti dalla sequenza scelta
+    pub fn total_rounds(&self) -> usize {
+        self.rules.round_schedule.total_rounds(self.starting_cards)
+    }
+
+    //  Carte per giocatore nel round corrente
+    pub fn cards_this_round(&self) -> usize {
+        self.rules
+            .round_schedule
+            .cards_for_round(self.schedule_round(), self.starting_cards)
+            .unwrap_or(0)
+    }
+
+    pub fn active_player_count(&self) -> usize {
+        self.players.iter().filter(|p| p.is_active()).count()
+    }
+
+    //  Round della sequenza di distribuzione: a vite la sequenza ricomincia
+    fn schedule_round(&self) -> usize {
+        let total = self.total_rounds();
+        if self.rules.uses_lives() && total > 0 {
+            (self.round_number - 1) % total + 1
+        } else {
+            self.round_number
+        }
+    }
+
+    //  Round alla cieca: con una carta sola ognuno vede quelle degli altri ma non la propria
+    pub fn is_blind_round(&self) -> bool {
+        self.rules.blind_one_card_round
+            && self.phase != GamePhase::Waiting
+            && self.cards_this_round() == 1
+    }
+
+    //  Mani visibili al giocatore indicato. Di norma solo la propria; nel round
+    //  alla cieca quelle degli avversari, con la propria coperta.
+    pub fn hand_views(&self, viewer_id: &str) -> Vec<HandView> {
+        let blind = self.is_blind_round();
+        self.players
+            .iter()
+            .filter(|p| p.id == viewer_id || (blind && p.is_active()))
+            .map(|p| HandView {
+                player_id: p.id.clone(),
+                cards: if blind && p.id == viewer_id {
+                    vec![None; p.hand.len()]
+                } else {
+                    p.hand.iter().cloned().map(Some).collect()
+                },
+            })
+            .collect()
+    }
+
+    //  Mano del giocatore separata da quelle degli avversari che puo vedere
+    pub fn visible_hands(&self, player_id: &str) -> (Vec<Option<Card>>, Vec<HandView>) {
+        let (own_hand, other_hands): (Vec<HandView>, Vec<HandView>) = self
+            .hand_views(player_id)
+            .into_iter()
+            .partition(|view| view.player_id == player_id);
+        let hand = own_hand.into_iter().next().map(|view| view.cards).unwrap_or_default();
+        (hand, other_hands)
+    }
+
+    //  Carta coperta del giocatore nel round alla cieca (la gioca senza conoscerla)
+    pub fn blind_card(&self, player_id: &str) -> Option<Card> {
+        if !self.is_blind_round() {
+            return None;
+        }
+        let index = self.player_index(player_id)?;
+        self.players[index].hand.first().cloned()
+    }
+
+    pub fn current_player_id(&self) -> Option<&str> {
+        self.players.get(self.current_player).map(|p| p.id.as_str())
+    }
+
+    //  Chi ospita il tavolo: il primo giocatore umano ancora presente
+    pub fn host_id(&self) -> Option<&str> {
+        self.players
+            .iter()
+            .find(|p| p.bot.is_none() && !p.away)
+            .map(|p| p.id.as_str())
+    }
+
+    pub fn dealer_id(&self) -> Option<&str> {
+        self.players.get(self.dealer).map(|p| p.id.as_str())
+    }
+
+    pub fn trick_leader_id(&self) -> Option<&str> {
+        self.players.get(self.trick_leader).map(|p| p.id.as_str())
+    }
+
+    //  Seed del mazzo per il round corrente
+    pub fn round_seed(&self) -> u64 {
+        round_seed(self.seed, self.round_number)
+    }
+
+    //  Hash del seed del round corrente, pubblicato prima di distribuire
+    pub fn seed_commitment(&self) -> String {
+        seed_commitment(self.round_seed())
+    }
+
+    //  Hash del seed per la prossima distribuzione
+    pub fn next_seed_commitment(&self) -> String {
+        let next_round = match self.phase {
+            GamePhase::Waiting | GamePhase::Finished => 1,
+            _ => self.round_number + 1,
+        };
+        seed_commitment(round_seed(self.seed, next_round))
+    }
+
+    //  Seed effettivo del mazzo corrente (seed del server + entropia dei giocatori)
+    pub fn deck_seed(&self) -> u64 {
+        deck_seed(self.round_seed(), &self.round_entropy)
+    }
+
+    //  Entropia di un giocatore da mescolare nella prossima distribuzione
+    pub fn contribute_entropy(&mut self, player_id: &str, entropy: String) -> Result<(), GameError> {
+        if self.player_index(player_id).is_none() {
+            return Err(GameError::PlayerNotFound);
+        }
+        if entropy.is_empty() || entropy.len() > MAX_ENTROPY_LEN {
+            return Err(GameError::InvalidEntropy { max_len: MAX_ENTROPY_LEN });
+        }
+        if self.pending_entropy.len() >= MAX_ENTROPY_CONTRIBUTIONS {
+            return Err(GameError::TooManyEntropyContributions {
+                max: MAX_ENTROPY_CONTRIBUTIONS,
+            });
+        }
+        self.pending_entropy.push(entropy);
+        Ok(())
+    }
+
+    fn shuffle_for_round(&mut self) {
+        self.round_entropy = std::mem::take(&mut self.pending_entropy);
+        self.deck = Card::shuffle_deck(self.deck_seed(), self.rules.deck_count());
+    }
+
+    //  Tempo a disposizione per l'azione corrente, in secondi
+    pub fn turn_time_limit(&self) -> Option<u64> {
+        match self.phase {
+            GamePhase::Predicting => self.rules.time_per_prediction,
+            GamePhase::Playing => self.rules.time_per_turn,
+            _ => None,
+        }
+    }
+
+    //  Il giocatore indicato ha esaurito il tempo del suo turno?
+    //  Chi ha lasciato il tavolo non va aspettato.
+    pub fn turn_expired(&self, player_id: &str) -> bool {
+        let Some(player) = self.player_index(player_id).map(|index| &self.players[index]) else {
+            return false;
+        };
+        // A buste chiuse il tempo vale per chiunque non abbia ancora previsto
+        let waiting_on_player = match self.phase {
+            GamePhase::Predicting if self.rules.sealed_predictions() => {
+                player.is_active() && player.prediction.is_none()
+            }
+            GamePhase::Predicting | GamePhase::Playing => self.current_player_id() == Some(player_id),
+            _ => false,
+        };
+        if !waiting_on_player {
+            return false;
+        }
+        player.away
+            || self
+                .turn_time_limit()
+                .is_some_and(|limit| self.turn_started_at.elapsed() >= Duration::from_secs(limit))
+    }
+
+    fn set_turn(&mut self, seat: usize) {
+        self.current_player = seat;
+        self.turn_started_at = Instant::now();
     }
 
     fn player_index(&self, player_id: &str) -> Option<usize> {
         self.players.iter().position(|p| p.id == player_id)
     }
+
+    //  Posto successivo in senso orario, saltando gli eliminati
+    fn next_seat(&self, seat: usize) -> usize {
+        let count = self.players.len();
+        if count == 0 {
+            return 0;
+        }
+        (1..=count)
+            .map(|step| (seat + step) % count)
+            .find(|&index| self.players[index].is_active())
+            .unwrap_or((seat + 1) % count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{bots::BotDifficulty, schedule::RoundSchedule};
+    use super::*;
+
+    fn card(text: &str) -> Card {
+        text.parse().unwrap()
+    }
+
+    fn cards(list: &[&str]) -> Vec<Card> {
+        list.iter().map(|c| card(c)).collect()
+    }
+
+    //  Tavolo gia in gioco con le mani indicate; apre il primo giocatore
+    fn playing(rules: GameRules, hands: &[&[&str]]) -> GameState {
+        let rules = GameRules { max_players: hands.len(), ..rules };
+        let mut game = GameState::with_seed(rules, 1).unwrap();
+        for index in 0..hands.len() {
+            game.add_player(Player::new(format!("p{index}"))).unwrap();
+        }
+        game.start_game().unwrap();
+        game.transition(GamePhase::Playing).unwrap();
+        for (player, hand) in game.players.iter_mut().zip(hands) {
+            player.hand = cards(hand);
+        }
+        game.trump_card = None;
+        game.set_turn(0);
+        game
+    }
+
+    fn play(game: &mut GameState, player_id: &str, card: &str, declaration: Option<WildDeclaration>) {
+        game.play_card(player_id, card.parse().unwrap(), declaration).unwrap();
+    }
+
+    #[test]
+    fn wild_card_does_not_set_the_leading_suit() {
+        let rules = GameRules { wild_card: Some("AD".parse().unwrap()), ..GameRules::default() };
+        let mut game = playing(rules, &[&["AD", "2C"], &["3D", "4S"], &["5S", "6D"]]);
+
+        play(&mut game, "p0", "AD", Some(WildDeclaration::Low));
+        assert_eq!(game.leading_suit, None);
+        assert_eq!(game.legal_cards("p1").unwrap(), cards(&["3D", "4S"]));
+
+        play(&mut game, "p1", "4S", None);
+        assert_eq!(game.leading_suit, Some(Suit::Spade));
+        assert_eq!(game.legal_cards("p2").unwrap(), cards(&["5S"]));
+
+        play(&mut game, "p2", "5S", None);
+        assert_eq!(game.end_turn().as_deref(), Some("p2"));
+    }
+
+    #[test]
+    fn trick_of_only_wild_cards_goes_to_the_first() {
+        let rules = GameRules {
+            wild_card: Some("AD".parse().unwrap()),
+            two_decks: true,
+            ..GameRules::default()
+        };
+        let mut game = playing(rules, &[&["AD", "2C"], &["AD", "3C"]]);
+        play(&mut game, "p0", "AD", Some(WildDeclaration::High));
+        play(&mut game, "p1", "AD", Some(WildDeclaration::High));
+        assert_eq!(game.end_turn().as_deref(), Some("p0"));
+        assert_eq!(game.players[0].tricks_won, 1);
+    }
+
+    #[test]
+    fn game_ends_after_the_last_scheduled_round() {
+        let schedules = [
+            RoundSchedule::Descending,
+            RoundSchedule::Ascending,
+            RoundSchedule::DownThenUp,
+            RoundSchedule::UpThenDown,
+            RoundSchedule::Fixed { rounds: 2 },
+        ];
+        for round_schedule in schedules {
+            let rules = GameRules {
+                max_players: 2,
+                auto_start: true,
+                cards_per_player: Some(3),
+                round_schedule,
+                ..GameRules::default()
+            };
+            let mut game = GameState::with_seed(rules, 3).unwrap();
+            game.seat_bot("bot-0".to_string(), BotDifficulty::Easy).unwrap();
+            game.seat_bot("bot-1".to_string(), BotDifficulty::Easy).unwrap();
+            while let Some(bot_id) = game.next_bot() {
+                let command = game.bot_command(&bot_id).unwrap();
+                game.handle(&bot_id, command).unwrap();
+            }
+
+            let rounds = game
+                .log
+                .records
+                .iter()
+                .filter(|r| matches!(r.entry, LogEntry::RoundEnded { .. }))
+                .count();
+            assert_eq!(game.phase, GamePhase::Finished, "{round_schedule:?}");
+            assert_eq!(rounds, game.total_rounds(), "{round_schedule:?}");
+            assert_eq!(game.round_number, game.total_rounds(), "{round_schedule:?}");
+        }
+    }
+
+    #[test]
+    fn identical_cards_from_two_decks_go_to_the_first_played() {
+        let rules = GameRules { two_decks: true, ..GameRules::default() };
+        let mut game = playing(rules, &[&["2S", "4C"], &["AS", "5C"], &["AS", "6C"]]);
+        play(&mut game, "p0", "2S", None);
+        play(&mut game, "p1", "AS", None);
+        play(&mut game, "p2", "AS", None);
+        assert_eq!(game.end_turn().as_deref(), Some("p1"));
+    }
+
+    #[test]
+    fn must_follow_the_leading_suit() {
+        let mut game = playing(GameRules::default(), &[&["3S", "RD"], &["5S", "AD", "2C"], &["AC", "2B"]]);
+        assert_eq!(game.legal_cards("p0").unwrap(), cards(&["3S", "RD"]));
+        assert_eq!(game.legal_cards("p1").unwrap(), Vec::new(), "non e il suo turno");
+
+        play(&mut game, "p0", "3S", None);
+        assert_eq!(game.legal_cards("p1").unwrap(), cards(&["5S"]));
+        assert_eq!(
+            game.play_card("p1", card("AD"), None),
+            Err(GameError::MustFollowSuit { suit: Suit::Spade })
+        );
+
+        // Senza il seme di uscita si gioca qualsiasi carta
+        play(&mut game, "p1", "5S", None);
+        assert_eq!(game.legal_cards("p2").unwrap(), cards(&["AC", "2B"]));
+    }
+
+    #[test]
+    fn must_trump_when_void_if_the_rule_is_on() {
+        let hands: &[&[&str]] = &[&["3S", "RD"], &["2B", "AC"]];
+        for must_trump_if_void in [false, true] {
+            let rules = GameRules { must_trump_if_void, ..GameRules::default() };
+            let mut game = playing(rules, hands);
+            game.trump_card = Some(card("4B"));
+            play(&mut game, "p0", "3S", None);
+
+            if must_trump_if_void {
+                assert_eq!(game.legal_cards("p1").unwrap(), cards(&["2B"]));
+                assert_eq!(
+                    game.play_card("p1", card("AC"), None),
+                    Err(GameError::MustPlayTrump { suit: Suit::Bastoni })
+                );
+            } else {
+                assert_eq!(game.legal_cards("p1").unwrap(), cards(&["2B", "AC"]));
+            }
+        }
+    }
 }
@@ -0,0 +1,357 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use super::{
+    bots::BotDifficulty,
+    card::Card,
+    commands::Command,
+    error::GameError,
+    game::{GamePhase, GameState},
+    ranking::WildDeclaration,
+    rules::GameRules,
+    scoring::RoundResult,
+};
+
+// ===========================================
+// REGISTRO DELLA PARTITA
+// ===========================================
+//
+// Ogni comando accettato viene aggiunto in ordine al registro, insieme ai fatti
+// che ne derivano (mani vinte, round conclusi). Ogni voce contiene l'hash della
+// precedente: modificare una voce invalida tutte le successive.
+// `GameState::replay` ricostruisce la partita rigiocando i comandi e controlla
+// che i fatti derivati coincidano.
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LogEntry {
+    Created { rules: GameRules, seed: u64 },
+    Joined {
+        player_id: String,
+        spectator: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        bot: Option<BotDifficulty>,
+    },
+    Left { player_id: String },
+    EntropyContributed { player_id: String, entropy: String },
+    Started,
+    Paused { player_id: String },
+    Resumed { player_id: String },
+    BotDifficultyChanged { player_id: String, bot_id: String, difficulty: BotDifficulty },
+    Prediction { player_id: String, prediction: u8, auto: bool },
+    CardPlayed {
+        player_id: String,
+        card: Card,
+        declaration: Option<WildDeclaration>,
+        auto: bool,
+    },
+    // Fatti derivati, verificati durante il replay
+    TrickWon { player_id: String, cards: Vec<(String, Card)> },
+    RoundEnded { round_number: usize, results: Vec<RoundResult> },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogRecord {
+    pub seq: usize,
+    pub entry: LogEntry,
+    pub hash: String, // sha256(hash precedente + voce), in esadecimale
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GameLog {
+    pub records: Vec<LogRecord>,
+}
+
+impl GameLog {
+    pub fn append(&mut self, entry: LogEntry) {
+        let previous = self.records.last().map(|r| r.hash.as_str()).unwrap_or("");
+        let hash = chain_hash(previous, &entry);
+        self.records.push(LogRecord { seq: self.records.len(), entry, hash });
+    }
+
+    //  Hash dell'ultima voce: annunciato ai client, li impegna sulla storia fin qui
+    pub fn head(&self) -> String {
+        self.records.last().map(|r| r.hash.clone()).unwrap_or_default()
+    }
+
+    //  Controlla numerazione e catena degli hash
+    pub fn verify(&self) -> Result<(), GameError> {
+        let mut previous = String::new();
+        for (index, record) in self.records.iter().enumerate() {
+            if record.seq != index {
+                return Err(invalid_log(index, "Numerazione non consecutiva"));
+            }
+            if record.hash != chain_hash(&previous, &record.entry) {
+                return Err(invalid_log(index, "Hash non valido, voce alterata"));
+            }
+            previous = record.hash.clone();
+        }
+        Ok(())
+    }
+}
+
+fn chain_hash(previous: &str, entry: &LogEntry) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(previous.as_bytes());
+    hasher.update(serde_json::to_vec(entry).unwrap_or_default());
+    hex::encode(hasher.finalize())
+}
+
+fn invalid_log(seq: usize, reason: impl Into<String>) -> GameError {
+    GameError::InvalidLog { seq, reason: reason.into() }
+}
+
+impl GameState {
+    //  Registro consultabile da chiunque. Finche la partita non e finita il seed
+    //  resta segreto (con l'entropia pubblica rivelerebbe ogni mano e ogni
+    //  distribuzione futura) e a buste chiuse lo restano le previsioni del round
+    //  non ancora rivelate. Gli hash sono quelli originali.
+    pub fn public_log(&self) -> Value {
+        let mut log = serde_json::to_value(&self.log).unwrap_or(Value::Null);
+        if self.phase == GamePhase::Finished {
+            return log;
+        }
+        let hide_predictions = self.rules.sealed_predictions() && !self.predictions_complete();
+        let Some(records) = log.get_mut("records").and_then(Value::as_array_mut) else {
+            return log;
+        };
+        let round_start = records
+            .iter()
+            .rposition(|r| matches!(r["entry"]["type"].as_str(), Some("round_ended" | "started")))
+            .map_or(0, |index| index + 1);
+        for (index, record) in records.iter_mut().enumerate() {
+            let entry = &mut record["entry"];
+            match entry["type"].as_str() {
+                Some("created") => entry["seed"] = Value::Null,
+                Some("prediction") if hide_predictions && index >= round_start => {
+                    entry["prediction"] = Value::Null
+                }
+                _ => {}
+            }
+        }
+        log
+    }
+
+    //  Ricostruisce la partita rigiocando il registro dall'inizio
+    pub fn replay(log: &GameLog) -> Result<GameState, GameError> {
+        log.verify()?;
+        let Some(LogEntry::Created { rules, seed }) = log.records.first().map(|r| &r.entry) else {
+            return Err(invalid_log(0, "Il registro deve iniziare con la creazione del tavolo"));
+        };
+        let mut game = GameState::with_seed(rules.clone(), *seed)?;
+
+        for record in &log.records[1..] {
+            // Le voci gia prodotte come effetto di un comando precedente
+            // (avvio automatico, mani vinte, fine round) si confrontano alla fine
+            if game.log.records.len() > record.seq {
+                continue;
+            }
+            game.replay_entry(&record.entry).map_err(|err| match err {
+                GameError::InvalidLog { .. } => err,
+                other => invalid_log(record.seq, other.to_string()),
+            })?;
+        }
+
+        // Il registro ricostruito deve coincidere voce per voce con l'originale
+        let hash_at = |log: &GameLog, i: usize| log.records.get(i).map(|r| r.hash.clone());
+        if let Some(index) = (0..log.records.len().max(game.log.records.len()))
+            .find(|&i| hash_at(log, i) != hash_at(&game.log, i))
+        {
+            return Err(invalid_log(index, "La voce non corrisponde al replay"));
+        }
+        Ok(game)
+    }
+
+    //  Ripete una voce del registro. I fatti derivati (mano vinta, fine round)
+    //  li produce il replay stesso con l'ultima carta: imposti da fuori sono un errore.
+    pub fn replay_entry(&mut self, entry: &LogEntry) -> Result<(), GameError> {
+        let seq = self.log.records.len();
+        match entry {
+            LogEntry::Created { .. } => return Err(invalid_log(seq, "Creazione ripetuta")),
+            LogEntry::Joined { player_id, bot: None, .. } => {
+                self.join(player_id)?;
+            }
+            LogEntry::Joined { player_id, bot: Some(difficulty), .. } => {
+                self.seat_bot(player_id.clone(), *difficulty)?;
+            }
+            LogEntry::Left { player_id } => {
+                self.leave(player_id);
+            }
+            LogEntry::EntropyContributed { player_id, entropy } => {
+                self.handle(player_id, Command::ContributeEntropy { entropy: entropy.clone() })?;
+            }
+            LogEntry::Started => {
+                self.begin(&mut Vec::new())?;
+            }
+            LogEntry::Paused { player_id } => {
+                self.handle(player_id, Command::PauseGame)?;
+            }
+            LogEntry::Resumed { player_id } => {
+                self.handle(player_id, Command::ResumeGame)?;
+            }
+            LogEntry::BotDifficultyChanged { player_id, bot_id, difficulty } => {
+                let command = Command::SetBotDifficulty { bot_id: bot_id.clone(), difficulty: *difficulty };
+                self.handle(player_id, command)?;
+            }
+            // Le azioni fatte d'ufficio si ripetono senza attendere il timer
+            LogEntry::Prediction { player_id, prediction, auto } => {
+                self.predict(&mut Vec::new(), player_id, *prediction, *auto)?;
+            }
+            LogEntry::CardPlayed { player_id, card, declaration, auto } => {
+                self.play(&mut Vec::new(), player_id, card.clone(), *declaration, *auto)?;
+            }
+            LogEntry::TrickWon { .. } => {
+                return Err(invalid_log(seq, "Mano vinta non prodotta dal replay"));
+            }
+            LogEntry::RoundEnded { .. } => {
+                return Err(invalid_log(seq, "Fine round non prodotta dal replay"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::rules::PredictionMode;
+    use super::*;
+
+    fn rules() -> GameRules {
+        GameRules { max_players: 3, auto_start: true, cards_per_player: Some(3), ..GameRules::default() }
+    }
+
+    //  Tavolo di soli bot che gioca al piu `moves` comandi
+    fn bot_game(rules: GameRules, moves: usize) -> GameState {
+        let mut game = GameState::with_seed(rules, 9).unwrap();
+        for index in 0..3 {
+            game.seat_bot(format!("bot-{index}"), BotDifficulty::Easy).unwrap();
+        }
+        for _ in 0..moves {
+            let Some(bot_id) = game.next_bot() else {
+                break;
+            };
+            let command = game.bot_command(&bot_id).unwrap();
+            game.handle(&bot_id, command).unwrap();
+        }
+        game
+    }
+
+    //  Ricalcola la catena dopo una modifica, come farebbe chi altera il registro
+    fn rechain(log: &GameLog) -> GameLog {
+        let mut chained = GameLog::default();
+        for record in &log.records {
+            chained.append(record.entry.clone());
+        }
+        chained
+    }
+
+    fn position(log: &GameLog, matches: impl Fn(&LogEntry) -> bool) -> usize {
+        log.records.iter().position(|r| matches(&r.entry)).unwrap()
+    }
+
+    fn assert_invalid(log: &GameLog, expected_seq: usize) {
+        match GameState::replay(log) {
+            Err(GameError::InvalidLog { seq, .. }) => assert_eq!(seq, expected_seq),
+            other => panic!("registro alterato accettato: {:?}", other.map(|g| g.phase)),
+        }
+    }
+
+    #[test]
+    fn finished_game_replays() {
+        let game = bot_game(rules(), usize::MAX);
+        assert_eq!(game.phase, GamePhase::Finished);
+        let replayed = GameState::replay(&game.log).unwrap();
+        assert_eq!(replayed.log.head(), game.log.head());
+    }
+
+    #[test]
+    fn altered_card_breaks_the_hash_chain() {
+        let mut log = bot_game(rules(), usize::MAX).log;
+        let index = position(&log, |e| matches!(e, LogEntry::CardPlayed { .. }));
+        if let LogEntry::CardPlayed { card, .. } = &mut log.records[index].entry {
+            let other = if card.to_string() == "2B" { "3B" } else { "2B" };
+            *card = other.parse().unwrap();
+        }
+        assert!(log.verify().is_err());
+        assert_invalid(&log, index);
+    }
+
+    #[test]
+    fn rehashed_card_is_caught_by_replay() {
+        let mut log = bot_game(rules(), usize::MAX).log;
+        // Un'altra carta della stessa mano del giocatore, giocata piu avanti
+        let index = position(&log, |e| matches!(e, LogEntry::CardPlayed { .. }));
+        let LogEntry::CardPlayed { player_id, .. } = log.records[index].entry.clone() else {
+            unreachable!()
+        };
+        let later = log.records[index + 1..]
+            .iter()
+            .find_map(|r| match &r.entry {
+                LogEntry::CardPlayed { player_id: id, card, .. } if *id == player_id => Some(card.clone()),
+                _ => None,
+            })
+            .unwrap();
+        if let LogEntry::CardPlayed { card, .. } = &mut log.records[index].entry {
+            *card = later;
+        }
+        let log = rechain(&log);
+        assert!(log.verify().is_ok());
+        assert!(matches!(GameState::replay(&log), Err(GameError::InvalidLog { .. })));
+    }
+
+    #[test]
+    fn rehashed_round_result_is_caught_by_replay() {
+        let mut log = bot_game(rules(), usize::MAX).log;
+        let index = position(&log, |e| matches!(e, LogEntry::RoundEnded { .. }));
+        if let LogEntry::RoundEnded { results, .. } = &mut log.records[index].entry {
+            results[0].points += 10;
+            results[0].total_score += 10;
+        }
+        let log = rechain(&log);
+        assert!(log.verify().is_ok());
+        assert_invalid(&log, index);
+    }
+
+    #[test]
+    fn sequence_numbers_must_be_consecutive() {
+        let mut log = bot_game(rules(), usize::MAX).log;
+        log.records[2].seq = 5;
+        assert!(matches!(log.verify(), Err(GameError::InvalidLog { seq: 2, .. })));
+        assert_invalid(&log, 2);
+    }
+
+    #[test]
+    fn public_log_hides_seed_and_sealed_predictions() {
+        let sealed = GameRules { prediction_mode: PredictionMode::Sealed, ..rules() };
+        let seed_of = |log: &Value| log["records"][0]["entry"]["seed"].clone();
+        let predictions = |log: &Value| -> Vec<Value> {
+            log["records"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter(|r| r["entry"]["type"] == "prediction")
+                .map(|r| r["entry"]["prediction"].clone())
+                .collect()
+        };
+
+        // Una sola busta chiusa: previsione e seed nascosti, hash originali
+        let game = bot_game(sealed.clone(), 1);
+        assert_eq!(game.phase, GamePhase::Predicting);
+        let public = game.public_log();
+        assert_eq!(seed_of(&public), Value::Null);
+        assert_eq!(predictions(&public), [Value::Null]);
+        assert_eq!(public["records"][1]["hash"], game.log.records[1].hash.as_str());
+
+        // Buste aperte: le previsioni si vedono, il seed ancora no
+        let game = bot_game(sealed.clone(), 3);
+        assert_eq!(game.phase, GamePhase::Playing);
+        let public = game.public_log();
+        assert_eq!(seed_of(&public), Value::Null);
+        assert!(predictions(&public).iter().all(Value::is_u64));
+
+        // A partita finita il registro e completo
+        let game = bot_game(sealed, usize::MAX);
+        assert_eq!(seed_of(&game.public_log()), 9);
+    }
+}
@@ -1,10 +1,28 @@
+// Modelli da persistere su MongoDB, non ancora collegati al server
+#[allow(dead_code)]
 pub mod users;
+pub mod bots;
 pub mod card;
+pub mod commands;
+pub mod error;
+pub mod events;
+pub mod expert;
+pub mod fairness;
 pub mod game;
+pub mod log;
+pub mod notation;
 pub mod player;
+pub mod ranking;
+pub mod rules;
+pub mod schedule;
+pub mod scoring;
 
 // Re-export commonly used types
-pub use users::*;
+pub use bots::*;
 pub use card::*;
+pub use commands::*;
+pub use error::*;
+pub use events::*;
 pub use game::*;
-pub use player::*;
+pub use ranking::*;
+pub use rules::*;
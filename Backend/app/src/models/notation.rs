@@ -0,0 +1,401 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use super::{
+    bots::BotDifficulty,
+    card::Card,
+    error::GameError,
+    game::{GamePhase, GameState},
+    log::{GameLog, LogEntry},
+    ranking::WildDeclaration,
+    rules::GameRules,
+};
+
+// ===========================================
+// NOTAZIONE DELLE PARTITE
+// ===========================================
+//
+// Formato testuale, una riga per fatto. I partecipanti sono indicati con un
+// alias (P1, P2, ...) assegnato in ordine di ingresso.
+//
+//   bisca 1
+//   seed 1234
+//   rules {"max_players":2}
+//   seat P1 <id>
+//   spectator P3 <id>
+//   bot P4 <id> easy
+//   difficulty P1 P4 strong   (chi ospita cambia il livello di un bot)
+//   entropy P1 "abc"
+//   start
+//   predict P1 2          (`2*` se fatta d'ufficio)
+//   trick P1 7D P2 AC+ : P2
+//   result 1 P1 2/1 +0 P2 0/1 +1
+//   pause P1 / resume P1 / leave P2
+//
+// Nelle mani: `+` / `-` matta alta o bassa, `*` carta giocata d'ufficio,
+// `: P2` vincitore della mano. Le righe vuote e quelle con `#` sono ignorate.
+
+pub const NOTATION_VERSION: u32 = 1;
+
+//  Scrive la partita in notazione a partire dal suo registro
+pub fn export_notation(log: &GameLog) -> String {
+    let mut out = format!("bisca {}\n", NOTATION_VERSION);
+    let mut aliases: HashMap<String, String> = HashMap::new();
+    let mut pending: Vec<String> = Vec::new(); // carte della mano in corso
+    let alias = |aliases: &HashMap<String, String>, id: &str| {
+        aliases.get(id).cloned().unwrap_or_else(|| id.to_string())
+    };
+
+    for (index, record) in log.records.iter().enumerate() {
+        // Una mano interrotta da altri fatti viene scritta a pezzi
+        if !matches!(record.entry, LogEntry::CardPlayed { .. } | LogEntry::TrickWon { .. })
+            && !pending.is_empty()
+        {
+            let _ = writeln!(out, "trick {}", pending.join(" "));
+            pending.clear();
+        }
+        match &record.entry {
+            LogEntry::Created { rules, seed } => {
+                let rules = serde_json::to_string(rules).unwrap_or_default();
+                let _ = writeln!(out, "seed {}\nrules {}", seed, rules);
+            }
+            LogEntry::Joined { player_id, spectator, bot } => {
+                let name = format!("P{}", aliases.len() + 1);
+                match bot {
+                    Some(difficulty) => {
+                        let _ = writeln!(out, "bot {} {} {}", name, player_id, difficulty_name(*difficulty));
+                    }
+                    None => {
+                        let kind = if *spectator { "spectator" } else { "seat" };
+                        let _ = writeln!(out, "{} {} {}", kind, name, player_id);
+                    }
+                }
+                aliases.insert(player_id.clone(), name);
+            }
+            LogEntry::BotDifficultyChanged { player_id, bot_id, difficulty } => {
+                let _ = writeln!(
+                    out,
+                    "difficulty {} {} {}",
+                    alias(&aliases, player_id),
+                    alias(&aliases, bot_id),
+                    difficulty_name(*difficulty)
+                );
+            }
+            LogEntry::Left { player_id } => {
+                let _ = writeln!(out, "leave {}", alias(&aliases, player_id));
+            }
+            LogEntry::EntropyContributed { player_id, entropy } => {
+                let quoted = serde_json::to_string(entropy).unwrap_or_default();
+                let _ = writeln!(out, "entropy {} {}", alias(&aliases, player_id), quoted);
+            }
+            LogEntry::Started => {
+                let _ = writeln!(out, "start\n# round 1");
+            }
+            LogEntry::Paused { player_id } => {
+                let _ = writeln!(out, "pause {}", alias(&aliases, player_id));
+            }
+            LogEntry::Resumed { player_id } => {
+                let _ = writeln!(out, "resume {}", alias(&aliases, player_id));
+            }
+            LogEntry::Prediction { player_id, prediction, auto } => {
+                let auto = if *auto { "*" } else { "" };
+                let _ = writeln!(out, "predict {} {}{}", alias(&aliases, player_id), prediction, auto);
+            }
+            LogEntry::CardPlayed { player_id, card, declaration, auto } => {
+                let declaration = match declaration {
+                    Some(WildDeclaration::High) => "+",
+                    Some(WildDeclaration::Low) => "-",
+                    None => "",
+                };
+                let auto = if *auto { "*" } else { "" };
+                pending.push(format!("{} {}{}{}", alias(&aliases, player_id), card, declaration, auto));
+            }
+            LogEntry::TrickWon { player_id, .. } => {
+                let _ = writeln!(out, "trick {} : {}", pending.join(" "), alias(&aliases, player_id));
+                pending.clear();
+            }
+            LogEntry::RoundEnded { round_number, results } => {
+                let _ = write!(out, "result {}", round_number);
+                for result in results {
+                    let prediction = result
+                        .prediction
+                        .map_or("-".to_string(), |p| p.to_string());
+                    let _ = write!(
+                        out,
+                        " {} {}/{} {:+}",
+                        alias(&aliases, &result.player_id),
+                        prediction,
+                        result.tricks_won,
+                        result.points
+                    );
+                }
+                out.push('\n');
+                if index + 1 < log.records.len() {
+                    let _ = writeln!(out, "# round {}", round_number + 1);
+                }
+            }
+        }
+    }
+    if !pending.is_empty() {
+        let _ = writeln!(out, "trick {}", pending.join(" "));
+    }
+    out
+}
+
+//  Legge una partita in notazione e la valida rigiocandola nel motore:
+//  vincitori delle mani e risultati dei round devono coincidere
+pub fn import_notation(text: &str) -> Result<GameState, GameError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+    match lines.next() {
+        Some((_, header)) if header == format!("bisca {}", NOTATION_VERSION) => {}
+        Some((line, _)) => return Err(invalid(line, "Intestazione attesa: bisca 1")),
+        None => return Err(invalid(1, "Notazione vuota")),
+    }
+
+    let mut seed: Option<u64> = None;
+    let mut rules = GameRules::default();
+    let mut game: Option<GameState> = None;
+    let mut aliases: HashMap<String, String> = HashMap::new();
+
+    for (line, text) in lines {
+        let (keyword, rest) = text.split_once(' ').unwrap_or((text, ""));
+        let rest = rest.trim();
+
+        // Intestazione della partita: seed e regole prima di ogni altro fatto
+        match (keyword, game.is_some()) {
+            ("seed", false) => {
+                seed = Some(rest.parse().map_err(|_| invalid(line, "Seed non valido"))?);
+                continue;
+            }
+            ("rules", false) => {
+                rules = serde_json::from_str(rest)
+                    .map_err(|err| invalid(line, format!("Regole non valide: {}", err)))?;
+                continue;
+            }
+            ("seed" | "rules", true) => return Err(invalid(line, "Seed e regole vanno dichiarati all'inizio")),
+            _ => {}
+        }
+        if game.is_none() {
+            let seed = seed.ok_or_else(|| invalid(line, "Seed mancante"))?;
+            game = Some(GameState::with_seed(rules.clone(), seed)?);
+        }
+        let Some(game) = game.as_mut() else {
+            continue;
+        };
+        let at_line = |err: GameError| match err {
+            GameError::InvalidNotation { .. } => err,
+            other => invalid(line, other.to_string()),
+        };
+        let tokens: Vec<&str> = rest.split_whitespace().collect();
+        let player = |alias: &str| {
+            aliases
+                .get(alias)
+                .cloned()
+                .ok_or_else(|| invalid(line, format!("Alias sconosciuto: {}", alias)))
+        };
+
+        match (keyword, tokens.as_slice()) {
+            ("seat" | "spectator", [alias, id]) => {
+                let spectator = keyword == "spectator";
+                game.replay_entry(&LogEntry::Joined { player_id: id.to_string(), spectator, bot: None })
+                    .map_err(at_line)?;
+                let seated = game.players.iter().any(|p| p.id == *id);
+                if seated != (keyword == "seat") {
+                    return Err(invalid(line, "Posto al tavolo diverso da quello registrato"));
+                }
+                aliases.insert(alias.to_string(), id.to_string());
+            }
+            ("bot", [alias, id, difficulty]) => {
+                let bot = Some(parse_difficulty(line, difficulty)?);
+                game.replay_entry(&LogEntry::Joined { player_id: id.to_string(), spectator: false, bot })
+                    .map_err(at_line)?;
+                aliases.insert(alias.to_string(), id.to_string());
+            }
+            ("difficulty", [host, bot, difficulty]) => {
+                let entry = LogEntry::BotDifficultyChanged {
+                    player_id: player(host)?,
+                    bot_id: player(bot)?,
+                    difficulty: parse_difficulty(line, difficulty)?,
+                };
+                game.replay_entry(&entry).map_err(at_line)?;
+            }
+            ("leave", [alias]) => {
+                game.replay_entry(&LogEntry::Left { player_id: player(alias)? }).map_err(at_line)?;
+            }
+            ("entropy", [alias, ..]) => {
+                let quoted = rest[alias.len()..].trim();
+                let entropy: String = serde_json::from_str(quoted)
+                    .map_err(|_| invalid(line, "Entropia non valida"))?;
+                game.replay_entry(&LogEntry::EntropyContributed { player_id: player(alias)?, entropy })
+                    .map_err(at_line)?;
+            }
+            ("start", []) => {
+                // Con l'avvio automatico la partita e gia iniziata all'ultimo ingresso
+                let auto_started = game.phase != GamePhase::Waiting
+                    && matches!(game.log.records.last().map(|r| &r.entry), Some(LogEntry::Started));
+                if !auto_started {
+                    game.replay_entry(&LogEntry::Started).map_err(at_line)?;
+                }
+            }
+            ("pause", [alias]) => {
+                game.replay_entry(&LogEntry::Paused { player_id: player(alias)? }).map_err(at_line)?;
+            }
+            ("resume", [alias]) => {
+                game.replay_entry(&LogEntry::Resumed { player_id: player(alias)? }).map_err(at_line)?;
+            }
+            ("predict", [alias, value]) => {
+                let (value, auto) = strip_auto(value);
+                let prediction = value.parse().map_err(|_| invalid(line, "Previsione non valida"))?;
+                let entry = LogEntry::Prediction { player_id: player(alias)?, prediction, auto };
+                game.replay_entry(&entry).map_err(at_line)?;
+            }
+            ("trick", tokens) => {
+                let (plays, winner) = match tokens {
+                    [plays @ .., ":", winner] => (plays, Some(player(winner)?)),
+                    plays => (plays, None),
+                };
+                if plays.len() % 2 != 0 {
+                    return Err(invalid(line, "Ogni carta va preceduta dal giocatore"));
+                }
+                let first_record = game.log.records.len();
+                for play in plays.chunks(2) {
+                    let entry = parse_play(line, player(play[0])?, play[1])?;
+                    game.replay_entry(&entry).map_err(at_line)?;
+                }
+                if let Some(winner) = winner {
+                    // La mano completa si chiude da sola con l'ultima carta
+                    let won_by = game.log.records[first_record..].iter().find_map(|r| match &r.entry {
+                        LogEntry::TrickWon { player_id, .. } => Some(player_id),
+                        _ => None,
+                    });
+                    match won_by {
+                        None => return Err(invalid(line, "Mano incompleta")),
+                        Some(won_by) if *won_by != winner => {
+                            return Err(invalid(line, "Vincitore della mano diverso dal replay"));
+                        }
+                        Some(_) => {}
+                    }
+                }
+            }
+            ("result", [round, entries @ ..]) => {
+                let round: usize = round.parse().map_err(|_| invalid(line, "Round non valido"))?;
+                let results = game
+                    .log
+                    .records
+                    .iter()
+                    .rev()
+                    .find_map(|r| match &r.entry {
+                        LogEntry::RoundEnded { round_number, results } if *round_number == round => {
+                            Some(results.clone())
+                        }
+                        _ => None,
+                    })
+                    .ok_or_else(|| invalid(line, "Round non concluso nel replay"))?;
+                if entries.len() != results.len() * 3 {
+                    return Err(invalid(line, "Numero di risultati diverso dal replay"));
+                }
+                for (entry, result) in entries.chunks(3).zip(&results) {
+                    let prediction = result.prediction.map_or("-".to_string(), |p| p.to_string());
+                    let expected = format!("{}/{}", prediction, result.tricks_won);
+                    let matches = player(entry[0])? == result.player_id
+                        && entry[1] == expected
+                        && entry[2].parse::<i32>().ok() == Some(result.points);
+                    if !matches {
+                        return Err(invalid(line, format!("Risultato di {} diverso dal replay", entry[0])));
+                    }
+                }
+            }
+            _ => return Err(invalid(line, format!("Riga non riconosciuta: {}", text))),
+        }
+    }
+
+    game.ok_or_else(|| invalid(1, "Nessun fatto da rigiocare"))
+}
+
+//  `7D`, `AC+`, `3S*`: carta con dichiarazione della matta e marcatore d'ufficio
+fn parse_play(line: usize, player_id: String, token: &str) -> Result<LogEntry, GameError> {
+    let (token, auto) = strip_auto(token);
+    let (token, declaration) = match token.chars().last() {
+        Some('+') => (&token[..token.len() - 1], Some(WildDeclaration::High)),
+        Some('-') => (&token[..token.len() - 1], Some(WildDeclaration::Low)),
+        _ => (token, None),
+    };
+    let card: Card = token.parse().map_err(|err| invalid(line, format!("{}", err)))?;
+    Ok(LogEntry::CardPlayed { player_id, card, declaration, auto })
+}
+
+fn difficulty_name(difficulty: BotDifficulty) -> String {
+    serde_json::to_value(difficulty)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn parse_difficulty(line: usize, token: &str) -> Result<BotDifficulty, GameError> {
+    serde_json::from_value(serde_json::Value::String(token.to_string()))
+        .map_err(|_| invalid(line, format!("Livello del bot sconosciuto: {}", token)))
+}
+
+fn strip_auto(token: &str) -> (&str, bool) {
+    match token.strip_suffix('*') {
+        Some(stripped) => (stripped, true),
+        None => (token, false),
+    }
+}
+
+fn invalid(line: usize, reason: impl Into<String>) -> GameError {
+    GameError::InvalidNotation { line, reason: reason.into() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //  Partita completa giocata solo da bot, con seed fisso
+    fn bot_game(rules: GameRules) -> GameState {
+        let mut game = GameState::with_seed(rules, 42).unwrap();
+        let difficulties = [BotDifficulty::Easy, BotDifficulty::Strong, BotDifficulty::Easy];
+        for (index, difficulty) in difficulties.into_iter().enumerate() {
+            game.seat_bot(format!("bot-{index}"), difficulty).unwrap();
+        }
+        while let Some(bot_id) = game.next_bot() {
+            let command = game.bot_command(&bot_id).expect("il bot di turno deve agire");
+            game.handle(&bot_id, command).unwrap();
+        }
+        game
+    }
+
+    fn rules() -> GameRules {
+        GameRules { max_players: 3, auto_start: true, cards_per_player: Some(4), ..GameRules::default() }
+    }
+
+    #[test]
+    fn export_import_replays_the_same_game() {
+        let game = bot_game(rules());
+        assert_eq!(game.phase, GamePhase::Finished);
+
+        let text = export_notation(&game.log);
+        let imported = import_notation(&text).unwrap();
+        assert_eq!(imported.log.head(), game.log.head());
+        assert_eq!(export_notation(&imported.log), text);
+
+        let replayed = GameState::replay(&imported.log).unwrap();
+        assert_eq!(replayed.phase, GamePhase::Finished);
+        let scores = |g: &GameState| g.players.iter().map(|p| (p.id.clone(), p.score)).collect::<Vec<_>>();
+        assert_eq!(scores(&replayed), scores(&game));
+    }
+
+    #[test]
+    fn altered_notation_is_rejected() {
+        let text = export_notation(&bot_game(rules()).log);
+        let altered = text.replacen("seed 42", "seed 43", 1);
+        assert_ne!(altered, text);
+        // Con un altro seed le mani distribuite non contengono le carte giocate
+        assert!(import_notation(&altered).is_err());
+    }
+}
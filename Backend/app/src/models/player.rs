@@ -1,25 +1,35 @@
-use axum::extract::ws::Message;
-use tokio::sync::mpsc::UnboundedSender;
+use super::{bots::BotDifficulty, card::Card};
 
-use super::card::Card;
-
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Player {
     pub id: String,
     pub hand: Vec<Card>,
     pub prediction: Option<u8>,
     pub tricks_won: u8,
-    pub sender: UnboundedSender<Message>,
+    pub score: i32, // punteggio cumulativo della partita
+    pub lives: i32,  // vite rimaste (modalita a vite)
+    pub eliminated_in: Option<usize>, // round in cui e stato eliminato
+    pub bot: Option<BotDifficulty>,   // giocatore controllato dal server
+    pub away: bool, // ha lasciato il tavolo a partita iniziata: al suo turno gioca il server
 }
 
 impl Player {
-    pub fn new(id: String, sender: UnboundedSender<Message>) -> Self {
+    pub fn new(id: String) -> Self {
         Self {
             id,
             hand: Vec::new(),
             prediction: None,
             tricks_won: 0,
-            sender,
+            score: 0,
+            lives: 0,
+            eliminated_in: None,
+            bot: None,
+            away: false,
         }
     }
+
+    //  Ancora in gioco (non eliminato)
+    pub fn is_active(&self) -> bool {
+        self.eliminated_in.is_none()
+    }
 }
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+use super::card::{suit_strength, Suit, Value};
+
+// ===========================================
+// GERARCHIA DELLE CARTE (sotto-documento `card_ranking`)
+// ===========================================
+//
+// Ordine dei valori:
+//   Standard  Asso > Re > Cavallo > Fante > 7 > 6 > 5 > 4 > 3 > 2
+//   Briscola  Asso > Tre > Re > Cavallo > Fante > 7 > 6 > 5 > 4 > 2
+// Ordine dei semi:
+//   Standard     Denari > Coppe > Spade > Bastoni, il seme piu forte batte gli altri
+//   LeadingOnly  nessuna gerarchia, vince solo il seme di uscita
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueOrder {
+    #[default]
+    Standard,
+    Briscola,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SuitOrder {
+    #[default]
+    Standard,
+    LeadingOnly,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct CardRanking {
+    pub values: ValueOrder,
+    pub suits: SuitOrder,
+}
+
+impl CardRanking {
+    //  Forza di un valore: piu alto vince
+    pub fn value_rank(&self, value: &Value) -> u8 {
+        match (self.values, value) {
+            (ValueOrder::Briscola, Value::Asso) => 12,
+            (ValueOrder::Briscola, Value::Tre) => 11,
+            _ => value.clone() as u8,
+        }
+    }
+
+    //  Forza di un seme, `None` se i semi non hanno gerarchia
+    pub fn suit_rank(&self, suit: &Suit) -> Option<u8> {
+        match self.suits {
+            SuitOrder::Standard => Some(suit_strength(suit)),
+            SuitOrder::LeadingOnly => None,
+        }
+    }
+
+    //  Seme che batte tutti gli altri, se la gerarchia ne prevede uno
+    pub fn strongest_suit(&self) -> Option<Suit> {
+        [Suit::Denari, Suit::Coppe, Suit::Spade, Suit::Bastoni]
+            .into_iter()
+            .filter_map(|suit| self.suit_rank(&suit).map(|rank| (rank, suit)))
+            .max_by_key(|(rank, _)| *rank)
+            .map(|(_, suit)| suit)
+    }
+}
+
+// ===========================================
+// MATTA (carta jolly)
+// ===========================================
+
+//  Dichiarazione di chi gioca la matta: carta piu alta o piu bassa della mano
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WildDeclaration {
+    High,
+    Low,
+}
@@ -0,0 +1,216 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    card::{max_starting_cards, Card, DECK_SIZE},
+    error::GameError,
+    ranking::CardRanking,
+    schedule::RoundSchedule,
+    scoring::ScoringSystem,
+};
+
+// Valori di default per i tavoli creati senza regole
+pub const DEFAULT_MIN_PLAYERS: usize = 2;
+pub const DEFAULT_MAX_PLAYERS: usize = 4;
+pub const DEFAULT_STARTING_CARDS: usize = 5;
+pub const DEFAULT_STARTING_LIVES: i32 = 5;
+
+// Limiti assoluti per un tavolo
+pub const MIN_TABLE_PLAYERS: usize = 2;
+pub const MAX_TABLE_PLAYERS: usize = 8;
+
+// Tempo di riflessione del bot esperto per ogni decisione
+pub const DEFAULT_BOT_TIME_BUDGET_MS: u64 = 500;
+pub const MAX_BOT_TIME_BUDGET_MS: u64 = 10_000;
+
+// ===========================================
+// GAME RULES (sotto-documento `rules` in init.js)
+// ===========================================
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameRules {
+    pub max_players: usize,
+    pub min_players: usize,
+    pub cards_per_player: Option<usize>,
+    pub time_per_turn: Option<u64>,       // secondi
+    pub time_per_prediction: Option<u64>, // secondi
+    pub allow_spectators: bool,
+    pub auto_start: bool,
+    pub friendly_mode: bool, // le partite amichevoli non contano nelle statistiche
+    pub show_trump_card: bool, // ogni round si scopre una briscola dal mazzo rimasto
+    pub prediction_required: bool, // se false si gioca senza previsioni
+    pub max_predictions: Option<u8>,
+    pub prediction_mode: PredictionMode,
+    pub hook_rule: bool, // l'ultimo a parlare non puo far tornare la somma (solo a turno)
+    pub must_trump_if_void: bool, // senza il seme di uscita si deve giocare briscola
+    pub two_decks: bool,          // due mazzi da 40 carte mescolati insieme, per i tavoli grandi
+    pub blind_one_card_round: bool, // con una carta sola si vedono le carte altrui ma non la propria
+    pub round_schedule: RoundSchedule,
+    pub card_ranking: CardRanking, // ordine dei valori e gerarchia dei semi
+    pub wild_card: Option<Card>,   // matta: chi la gioca dichiara se e la piu alta o la piu bassa
+    pub bot_time_budget_ms: u64,   // millisecondi per ogni decisione del bot esperto
+    pub victory_conditions: VictoryConditions,
+    pub scoring_system: ScoringSystem,
+}
+
+//  Come si raccolgono le previsioni
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PredictionMode {
+    Sequential, // a turno, partendo alla sinistra del mazziere
+    Sealed,     // tutti in segreto, rivelate insieme quando sono complete
+}
+
+//  Come si vince la partita
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VictoryMode {
+    HighestScore, // vince chi ha piu punti alla fine dei round
+    Lives,        // ogni errore costa vite, vince l'ultimo sopravvissuto
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VictoryConditions {
+    pub mode: VictoryMode,
+    pub starting_lives: i32,
+}
+
+impl Default for VictoryConditions {
+    fn default() -> Self {
+        Self {
+            mode: VictoryMode::HighestScore,
+            starting_lives: DEFAULT_STARTING_LIVES,
+        }
+    }
+}
+
+impl Default for GameRules {
+    fn default() -> Self {
+        Self {
+            max_players: DEFAULT_MAX_PLAYERS,
+            min_players: DEFAULT_MIN_PLAYERS,
+            cards_per_player: None,
+            time_per_turn: None,
+            time_per_prediction: None,
+            allow_spectators: true,
+            auto_start: false,
+            friendly_mode: false,
+            show_trump_card: false,
+            prediction_required: true,
+            max_predictions: None,
+            prediction_mode: PredictionMode::Sequential,
+            hook_rule: true,
+            must_trump_if_void: false,
+            two_decks: false,
+            blind_one_card_round: false,
+            round_schedule: RoundSchedule::default(),
+            card_ranking: CardRanking::default(),
+            wild_card: None,
+            bot_time_budget_ms: DEFAULT_BOT_TIME_BUDGET_MS,
+            victory_conditions: VictoryConditions::default(),
+            scoring_system: ScoringSystem::default(),
+        }
+    }
+}
+
+impl GameRules {
+    //  Controlla la coerenza delle regole alla creazione del tavolo
+    pub fn validate(&self) -> Result<(), GameError> {
+        if self.min_players < MIN_TABLE_PLAYERS {
+            return Err(invalid("min_players", format!("Servono almeno {} giocatori", MIN_TABLE_PLAYERS)));
+        }
+        if self.max_players > MAX_TABLE_PLAYERS {
+            return Err(invalid("max_players", format!("Massimo {} giocatori per tavolo", MAX_TABLE_PLAYERS)));
+        }
+        if self.min_players > self.max_players {
+            return Err(invalid("min_players", "min_players non puo superare max_players"));
+        }
+        if self.cards_per_player == Some(0) {
+            return Err(invalid("cards_per_player", "cards_per_player deve essere almeno 1"));
+        }
+        // Il tavolo al completo deve poter ricevere la mano iniziale
+        if let Some(cards) = self.cards_per_player {
+            let max = max_starting_cards(self.max_players, self.deck_count());
+            if cards > max {
+                return Err(invalid(
+                    "cards_per_player",
+                    format!(
+                        "Con {} giocatori e {} carte la mano massima e di {} carte",
+                        self.max_players,
+                        DECK_SIZE * self.deck_count(),
+                        max
+                    ),
+                ));
+            }
+        }
+        if self.round_schedule == (RoundSchedule::Fixed { rounds: 0 }) {
+            return Err(invalid("round_schedule", "Serve almeno un round"));
+        }
+        if self.victory_conditions.mode == VictoryMode::Lives {
+            if self.victory_conditions.starting_lives <= 0 {
+                return Err(invalid("victory_conditions", "starting_lives deve essere positivo"));
+            }
+            if !self.prediction_required {
+                return Err(invalid(
+                    "victory_conditions",
+                    "La modalita a vite richiede le previsioni",
+                ));
+            }
+        }
+        if self.time_per_turn == Some(0) || self.time_per_prediction == Some(0) {
+            return Err(invalid("time_per_turn", "I tempi per turno devono essere positivi"));
+        }
+        if self.bot_time_budget_ms == 0 || self.bot_time_budget_ms > MAX_BOT_TIME_BUDGET_MS {
+            return Err(invalid(
+                "bot_time_budget_ms",
+                format!("Il tempo per decisione dei bot va da 1 a {} ms", MAX_BOT_TIME_BUDGET_MS),
+            ));
+        }
+        Ok(())
+    }
+
+    //  Mazzi da 40 carte usati al tavolo
+    pub fn deck_count(&self) -> usize {
+        if self.two_decks {
+            2
+        } else {
+            1
+        }
+    }
+
+    pub fn sealed_predictions(&self) -> bool {
+        self.prediction_mode == PredictionMode::Sealed
+    }
+
+    pub fn is_wild(&self, card: &Card) -> bool {
+        self.wild_card.as_ref() == Some(card)
+    }
+
+    pub fn uses_lives(&self) -> bool {
+        self.victory_conditions.mode == VictoryMode::Lives
+    }
+
+    //  Mano iniziale per il numero di giocatori seduti
+    pub fn starting_cards(&self, num_players: usize) -> Result<usize, GameError> {
+        let max = max_starting_cards(num_players, self.deck_count());
+        let cards = self
+            .cards_per_player
+            .unwrap_or_else(|| DEFAULT_STARTING_CARDS.min(max));
+        if cards == 0 || cards > max {
+            return Err(invalid(
+                "cards_per_player",
+                format!(
+                    "Impossibile distribuire {} carte a {} giocatori con un mazzo da {}",
+                    cards,
+                    num_players,
+                    DECK_SIZE * self.deck_count()
+                ),
+            ));
+        }
+        Ok(cards)
+    }
+}
+
+fn invalid(field: &'static str, reason: impl Into<String>) -> GameError {
+    GameError::InvalidRules { field, reason: reason.into() }
+}
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+// ===========================================
+// SEQUENZA DEI ROUND
+// ===========================================
+//
+// Carte per giocatore in ogni round, dato `starting_cards` (la mano piu grande).
+// Esempio con 3 carte:
+//   Descending  3, 2, 1
+//   Ascending   1, 2, 3
+//   DownThenUp  3, 2, 1, 2, 3
+//   UpThenDown  1, 2, 3, 2, 1
+//   Fixed(4)    3, 3, 3, 3
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RoundSchedule {
+    #[default]
+    Descending,
+    Ascending,
+    DownThenUp,
+    UpThenDown,
+    Fixed { rounds: usize },
+}
+
+impl RoundSchedule {
+    //  Numero totale di round della partita
+    pub fn total_rounds(&self, starting_cards: usize) -> usize {
+        match self {
+            RoundSchedule::Descending | RoundSchedule::Ascending => starting_cards,
+            RoundSchedule::DownThenUp | RoundSchedule::UpThenDown => {
+                (2 * starting_cards).saturating_sub(1)
+            }
+            RoundSchedule::Fixed { rounds } => *rounds,
+        }
+    }
+
+    //  Carte per giocatore nel round indicato (i round partono da 1)
+    pub fn cards_for_round(&self, round_number: usize, starting_cards: usize) -> Option<usize> {
+        if round_number == 0 || round_number > self.total_rounds(starting_cards) {
+            return None;
+        }
+        let cards = match self {
+            RoundSchedule::Descending => starting_cards + 1 - round_number,
+            RoundSchedule::Ascending => round_number,
+            RoundSchedule::DownThenUp => {
+                if round_number <= starting_cards {
+                    starting_cards + 1 - round_number
+                } else {
+                    round_number + 1 - starting_cards
+                }
+            }
+            RoundSchedule::UpThenDown => {
+                if round_number <= starting_cards {
+                    round_number
+                } else {
+                    2 * starting_cards - round_number
+                }
+            }
+            RoundSchedule::Fixed { .. } => starting_cards,
+        };
+        Some(cards)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sequence(schedule: RoundSchedule, starting_cards: usize) -> Vec<usize> {
+        (1..=schedule.total_rounds(starting_cards))
+            .map(|round| schedule.cards_for_round(round, starting_cards).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn cards_for_each_schedule() {
+        assert_eq!(sequence(RoundSchedule::Descending, 3), [3, 2, 1]);
+        assert_eq!(sequence(RoundSchedule::Ascending, 3), [1, 2, 3]);
+        assert_eq!(sequence(RoundSchedule::DownThenUp, 3), [3, 2, 1, 2, 3]);
+        assert_eq!(sequence(RoundSchedule::UpThenDown, 3), [1, 2, 3, 2, 1]);
+        assert_eq!(sequence(RoundSchedule::Fixed { rounds: 4 }, 3), [3, 3, 3, 3]);
+    }
+
+    #[test]
+    fn no_cards_outside_the_game() {
+        let schedules = [
+            RoundSchedule::Descending,
+            RoundSchedule::Ascending,
+            RoundSchedule::DownThenUp,
+            RoundSchedule::UpThenDown,
+            RoundSchedule::Fixed { rounds: 4 },
+        ];
+        for schedule in schedules {
+            let after_last = schedule.total_rounds(3) + 1;
+            assert_eq!(schedule.cards_for_round(0, 3), None, "{schedule:?}");
+            assert_eq!(schedule.cards_for_round(after_last, 3), None, "{schedule:?}");
+        }
+    }
+
+    #[test]
+    fn total_rounds_for_each_schedule() {
+        assert_eq!(RoundSchedule::Descending.total_rounds(5), 5);
+        assert_eq!(RoundSchedule::Ascending.total_rounds(5), 5);
+        assert_eq!(RoundSchedule::DownThenUp.total_rounds(5), 9);
+        assert_eq!(RoundSchedule::UpThenDown.total_rounds(5), 9);
+        assert_eq!(RoundSchedule::Fixed { rounds: 7 }.total_rounds(5), 7);
+        // Con una carta sola andata e ritorno coincidono
+        assert_eq!(sequence(RoundSchedule::DownThenUp, 1), [1]);
+        assert_eq!(sequence(RoundSchedule::UpThenDown, 1), [1]);
+    }
+}
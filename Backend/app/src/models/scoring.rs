@@ -0,0 +1,229 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+
+// ===========================================
+// SISTEMI DI PUNTEGGIO (sotto-documento `scoring_system`)
+// ===========================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoringFormula {
+    BonusPlusTricks, // esatta: bonus fisso + punti per presa; sbagliata: 0
+    MissPenalty,     // esatta: bonus + prese; sbagliata: penalita per ogni presa di scarto
+    ExactOrNothing,  // esatta: solo il bonus fisso; sbagliata: 0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringSystem {
+    pub formula: ScoringFormula,
+    pub exact_bonus: i32,
+    pub points_per_trick: i32,
+    pub miss_penalty: i32,     // per presa di scarto (solo MissPenalty)
+    pub zero_call_bonus: i32,  // extra per chi chiama zero e non prende nulla
+}
+
+impl Default for ScoringSystem {
+    fn default() -> Self {
+        Self {
+            formula: ScoringFormula::BonusPlusTricks,
+            exact_bonus: 10,
+            points_per_trick: 1,
+            miss_penalty: 1,
+            zero_call_bonus: 0,
+        }
+    }
+}
+
+//  Dettaglio dei punti di un round, la somma e `total()`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScoreBreakdown {
+    pub exact_bonus: i32,
+    pub trick_points: i32,
+    pub miss_penalty: i32,
+    pub zero_call_bonus: i32,
+}
+
+impl ScoreBreakdown {
+    pub fn total(&self) -> i32 {
+        self.exact_bonus + self.trick_points + self.miss_penalty + self.zero_call_bonus
+    }
+}
+
+impl ScoringSystem {
+    //  Punti di un giocatore per il round; senza previsione contano solo le prese
+    pub fn score(&self, prediction: Option<u8>, tricks_won: u8) -> ScoreBreakdown {
+        let tricks = tricks_won as i32;
+        let Some(called) = prediction else {
+            return ScoreBreakdown {
+                trick_points: tricks * self.points_per_trick,
+                ..ScoreBreakdown::default()
+            };
+        };
+
+        let mut breakdown = ScoreBreakdown::default();
+        if called == tricks_won {
+            breakdown.exact_bonus = self.exact_bonus;
+            if self.formula != ScoringFormula::ExactOrNothing {
+                breakdown.trick_points = tricks * self.points_per_trick;
+            }
+            if called == 0 {
+                breakdown.zero_call_bonus = self.zero_call_bonus;
+            }
+        } else if self.formula == ScoringFormula::MissPenalty {
+            let miss = (called as i32 - tricks).abs();
+            breakdown.miss_penalty = -miss * self.miss_penalty;
+        }
+        breakdown
+    }
+}
+
+// ===========================================
+// RISULTATI DI ROUND E CLASSIFICA
+// ===========================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoundResult {
+    pub player_id: String,
+    pub prediction: Option<u8>,
+    pub tricks_won: u8,
+    pub success: bool,
+    pub points: i32,      // punti ottenuti in questo round
+    pub breakdown: ScoreBreakdown,
+    pub total_score: i32, // punteggio cumulativo dopo il round
+    pub lives: Option<i32>, // vite rimaste, solo in modalita a vite
+    pub eliminated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum GameResult {
+    Victory,
+    Defeat,
+    Draw,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Standing {
+    pub player_id: String,
+    pub score: i32,
+    pub rank: usize, // 1 = primo; a pari punteggio stesso rank
+    pub result: GameResult,
+}
+
+//  Classifica finale con gestione dei pari merito (1, 1, 3, ...).
+//  Ogni voce e (player_id, punteggio mostrato, chiave di ordinamento).
+//  Vince chi e primo da solo; se il primo posto e condiviso e pareggio.
+pub fn rank_players<K: Ord + Copy>(entries: &[(String, i32, K)]) -> Vec<Standing> {
+    let mut sorted: Vec<&(String, i32, K)> = entries.iter().collect();
+    sorted.sort_by_key(|(_, _, key)| Reverse(*key));
+
+    let best = sorted.first().map(|(_, _, key)| *key);
+    let leaders = sorted.iter().filter(|(_, _, key)| Some(*key) == best).count();
+
+    let mut standings: Vec<Standing> = Vec::with_capacity(sorted.len());
+    let mut previous_key: Option<K> = None;
+    for (position, (player_id, score, key)) in sorted.into_iter().enumerate() {
+        let rank = match standings.last() {
+            Some(previous) if previous_key == Some(*key) => previous.rank,
+            _ => position + 1,
+        };
+        previous_key = Some(*key);
+        let result = match (rank, leaders) {
+            (1, 1) => GameResult::Victory,
+            (1, _) => GameResult::Draw,
+            _ => GameResult::Defeat,
+        };
+        standings.push(Standing {
+            player_id: player_id.clone(),
+            score: *score,
+            rank,
+            result,
+        });
+    }
+    standings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ScoringFormula::*;
+    use super::*;
+
+    fn system(formula: ScoringFormula) -> ScoringSystem {
+        ScoringSystem { formula, exact_bonus: 10, points_per_trick: 2, miss_penalty: 3, zero_call_bonus: 5 }
+    }
+
+    fn parts(breakdown: &ScoreBreakdown) -> (i32, i32, i32, i32) {
+        (breakdown.exact_bonus, breakdown.trick_points, breakdown.miss_penalty, breakdown.zero_call_bonus)
+    }
+
+    #[test]
+    fn score_for_each_formula() {
+        // (formula, previsione, prese, (bonus, prese, penalita, zero))
+        let cases = [
+            (BonusPlusTricks, 2, 2, (10, 4, 0, 0)),
+            (BonusPlusTricks, 2, 3, (0, 0, 0, 0)),
+            (BonusPlusTricks, 0, 0, (10, 0, 0, 5)),
+            (MissPenalty, 2, 2, (10, 4, 0, 0)),
+            (MissPenalty, 1, 3, (0, 0, -6, 0)),
+            (MissPenalty, 3, 1, (0, 0, -6, 0)),
+            (MissPenalty, 0, 0, (10, 0, 0, 5)),
+            (ExactOrNothing, 2, 2, (10, 0, 0, 0)),
+            (ExactOrNothing, 2, 1, (0, 0, 0, 0)),
+            (ExactOrNothing, 0, 0, (10, 0, 0, 5)),
+        ];
+        for (formula, called, tricks, expected) in cases {
+            let breakdown = system(formula).score(Some(called), tricks);
+            assert_eq!(parts(&breakdown), expected, "{formula:?} {called}/{tricks}");
+            let (bonus, trick_points, penalty, zero) = expected;
+            assert_eq!(breakdown.total(), bonus + trick_points + penalty + zero);
+        }
+    }
+
+    #[test]
+    fn without_prediction_only_tricks_count() {
+        for formula in [BonusPlusTricks, MissPenalty, ExactOrNothing] {
+            assert_eq!(parts(&system(formula).score(None, 3)), (0, 6, 0, 0), "{formula:?}");
+            assert_eq!(system(formula).score(None, 0).total(), 0, "{formula:?}");
+        }
+    }
+
+    fn ranks(entries: &[(&str, i32)]) -> Vec<(String, usize, GameResult)> {
+        let entries: Vec<(String, i32, i32)> =
+            entries.iter().map(|(id, score)| (id.to_string(), *score, *score)).collect();
+        rank_players(&entries)
+            .into_iter()
+            .map(|s| (s.player_id, s.rank, s.result))
+            .collect()
+    }
+
+    fn expected(list: &[(&str, usize, GameResult)]) -> Vec<(String, usize, GameResult)> {
+        list.iter().map(|(id, rank, result)| (id.to_string(), *rank, result.clone())).collect()
+    }
+
+    #[test]
+    fn shared_first_place_is_a_draw() {
+        let standings = ranks(&[("c", 5), ("a", 12), ("b", 12)]);
+        assert_eq!(
+            standings,
+            expected(&[
+                ("a", 1, GameResult::Draw),
+                ("b", 1, GameResult::Draw),
+                ("c", 3, GameResult::Defeat),
+            ])
+        );
+    }
+
+    #[test]
+    fn single_leader_wins_and_ties_below_share_the_rank() {
+        let standings = ranks(&[("a", 20), ("b", 7), ("c", 7), ("d", -3)]);
+        assert_eq!(
+            standings,
+            expected(&[
+                ("a", 1, GameResult::Victory),
+                ("b", 2, GameResult::Defeat),
+                ("c", 2, GameResult::Defeat),
+                ("d", 4, GameResult::Defeat),
+            ])
+        );
+    }
+}
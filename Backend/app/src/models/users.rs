@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 
+use super::scoring::GameResult;
+
 // ===========================================
 // USER (Frequent read/write - Authentication)
 // ===========================================
@@ -131,13 +133,6 @@ impl UserStatistics {
     }
 }
 
-#[derive(Debug, Clone)]
-pub enum GameResult {
-    Victory,
-    Defeat,
-    Draw,
-}
-
 impl UserProfile {
     // Crea profilo iniziale per nuovo utente
     pub fn new(user_id: String, name: String, surname: String) -> Self {
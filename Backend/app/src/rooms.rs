@@ -0,0 +1,133 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use axum::extract::ws::Message;
+use tokio::sync::{mpsc::UnboundedSender, Mutex, RwLock};
+use uuid::Uuid;
+
+use crate::models::{GameError, GameRules, GameState};
+use crate::websocket::advance;
+
+// ===========================================
+// TAVOLO
+// ===========================================
+
+//  Partita piu le connessioni dei partecipanti (giocatori e spettatori).
+//  Il motore resta indipendente dal trasporto: le connessioni vivono qui.
+pub struct Table {
+    pub game: GameState,
+    pub connections: HashMap<String, UnboundedSender<Message>>,
+    pub bots_running: bool, // c'e gia un task che fa giocare i bot
+    pub closed: bool,       // tolto dal registro: non accetta piu connessioni
+}
+
+impl Table {
+    pub fn new(game: GameState) -> Self {
+        Self { game, connections: HashMap::new(), bots_running: false, closed: false }
+    }
+
+    //  Chiude il tavolo: le connessioni aperte si chiudono con il loro canale
+    fn close(&mut self) {
+        self.closed = true;
+        self.connections.clear();
+    }
+
+    //  Restano solo bot o giocatori usciti
+    pub fn is_abandoned(&self) -> bool {
+        self.game.players.iter().all(|p| p.bot.is_some() || p.away)
+    }
+}
+
+// ===========================================
+// GAME REGISTRY
+// ===========================================
+
+pub type SharedGame = Arc<Mutex<Table>>;
+
+//  Un tavolo senza connessioni per tutto questo tempo viene eliminato
+const IDLE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+//  Intervallo dei controlli periodici su ogni tavolo (anche i tempi di turno)
+const TABLE_TICK: Duration = Duration::from_secs(1);
+
+//  Registro dei tavoli attivi, indicizzato per game_id
+#[derive(Clone, Default)]
+pub struct GameRegistry {
+    games: Arc<RwLock<HashMap<String, SharedGame>>>,
+}
+
+impl GameRegistry {
+    //  Crea un nuovo tavolo con le regole indicate e restituisce il suo id.
+    //  Il seed lo sceglie sempre il server: chi lo conosce conosce ogni mazzo.
+    pub async fn create(&self, rules: GameRules) -> Result<String, GameError> {
+        let game = GameState::new_game(rules)?;
+        let game_id = Uuid::new_v4().to_string();
+        let table = Arc::new(Mutex::new(Table::new(game)));
+        self.games.write().await.insert(game_id.clone(), table.clone());
+        tokio::spawn(self.clone().watch(game_id.clone(), table));
+        Ok(game_id)
+    }
+
+    pub async fn get(&self, game_id: &str) -> Option<SharedGame> {
+        self.games.read().await.get(game_id).cloned()
+    }
+
+    //  Elimina il tavolo e chiude le connessioni ancora aperte
+    pub async fn remove(&self, game_id: &str) -> Option<SharedGame> {
+        let table = self.get(game_id).await?;
+        let mut guard = table.lock().await;
+        self.unregister(game_id, &table, &mut guard).await;
+        drop(guard);
+        Some(table)
+    }
+
+    //  Elimina il tavolo solo se non ci sono piu giocatori umani presenti.
+    //  Il controllo si fa tenendo il tavolo, cosi nessuno entra nel frattempo;
+    //  il registro si blocca solo dopo, per non fermare le altre partite.
+    pub async fn remove_if_empty(&self, game_id: &str) -> bool {
+        let Some(table) = self.get(game_id).await else {
+            return false;
+        };
+        let mut guard = table.lock().await;
+        if !guard.is_abandoned() {
+            return false;
+        }
+        self.unregister(game_id, &table, &mut guard).await;
+        true
+    }
+
+    //  Toglie il tavolo dal registro (se e ancora quello registrato) e lo chiude.
+    //  Va chiamata tenendo il tavolo: chi lo aveva gia ottenuto lo trova chiuso.
+    async fn unregister(&self, game_id: &str, table: &SharedGame, guard: &mut Table) {
+        let mut games = self.games.write().await;
+        if games.get(game_id).is_some_and(|current| Arc::ptr_eq(current, table)) {
+            games.remove(game_id);
+        }
+        guard.close();
+    }
+
+    //  Controlli periodici del tavolo finche resta aperto: allo scadere del
+    //  tempo (o se un giocatore e uscito) il server gioca al suo posto, e chi
+    //  crea un tavolo senza usarlo non lo lascia in memoria per sempre
+    async fn watch(self, game_id: String, table: SharedGame) {
+        let mut ticker = tokio::time::interval(TABLE_TICK);
+        let mut idle_since = Instant::now();
+        loop {
+            ticker.tick().await;
+            let mut guard = table.lock().await;
+            if guard.closed {
+                return;
+            }
+            if !guard.connections.is_empty() {
+                idle_since = Instant::now();
+            } else if idle_since.elapsed() >= IDLE_TIMEOUT {
+                self.unregister(&game_id, &table, &mut guard).await;
+                return;
+            }
+            let events = guard.game.expire_turns();
+            advance(&table, &mut guard, events);
+        }
+    }
+}
@@ -1,65 +1,66 @@
 use axum::{
-    extract::ws::{Message, WebSocket, WebSocketUpgrade},
-    response::IntoResponse,
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, State,
+    },
+    http::StatusCode,
+    response::{IntoResponse, Response},
     routing::get,
     Router,
 };
 use futures::{SinkExt, StreamExt};
-use std::sync::Arc;
-use tokio::sync::{Mutex, mpsc};
+use tokio::sync::mpsc;
 use uuid::Uuid;
-use serde_json::json;
+use serde_json::{json, Value};
 
-use crate::models::{Card, GameState, Player};
+use crate::models::{Audience, BotDifficulty, Card, Command, Envelope, GameError, WildDeclaration};
+use crate::https_server::AppState;
+use crate::rooms::{GameRegistry, SharedGame, Table};
 
-//creazione routes partendo dallo stato del gioco condiviso
-pub fn websocket_routes(game_state: Arc<Mutex<GameState>>) -> Router {
-    Router::new().route("/game", get(move |ws: WebSocketUpgrade| {
-        let state = game_state.clone();
-        async move { ws.on_upgrade(move |socket| handle_socket(socket, state)) }
-    }))
+//creazione routes: ogni game_id ha il proprio tavolo nel registro
+pub fn websocket_routes() -> Router<AppState> {
+    Router::new().route("/games/:game_id/ws", get(ws_handler))
+}
+
+async fn ws_handler(
+    ws: WebSocketUpgrade,
+    Path(game_id): Path<String>,
+    State(state): State<AppState>,
+) -> Response {
+    let registry = state.games;
+    match registry.get(&game_id).await {
+        Some(state) => ws
+            .on_upgrade(move |socket| handle_socket(socket, state, registry, game_id))
+            .into_response(),
+        None => (StatusCode::NOT_FOUND, "Partita inesistente").into_response(),
+    }
 }
 
 //
-async fn handle_socket(socket: WebSocket, state: Arc<Mutex<GameState>>) {
+async fn handle_socket(socket: WebSocket, state: SharedGame, registry: GameRegistry, game_id: String) {
     let (mut sender, mut receiver) = socket.split();
     let (tx, mut rx) = mpsc::unbounded_channel();
     let player_id = Uuid::new_v4().to_string();
 
-    let player = Player::new(player_id.clone(), tx.clone());
     {
-        let mut game = state.lock().await;
-        if let Err(err) = game.add_player(player) {
-            eprintln!("Impossibile aggiungere il giocatore: {}", err);
+        let mut table = state.lock().await;
+        // Il tavolo puo essere stato chiuso tra la richiesta e l'upgrade
+        if table.closed {
+            let _ = sender.send(Message::Text(error_frame(&GameError::TableClosed))).await;
             return;
         }
-        let players_list: Vec<String> = game.players.iter().map(|p| p.id.clone()).collect();
-        let current_turn: Vec<serde_json::Value> = game
-            .current_turn_cards
-            .iter()
-            .map(|(id, card)| json!({ "player_id": id, "card": card }))
-            .collect();
-        let hand = game
-            .players
-            .iter()
-            .find(|p| p.id == player_id)
-            .map(|p| p.hand.clone())
-            .unwrap_or_default();
-        let welcome_msg = json!({
-            "event": "welcome",
-            "data": {
-                "player_id": player_id.clone(),
-                "round_number": game.round_number,
-                "starting_cards": game.starting_cards,
-                "hand": hand,
-                "players": players_list,
-                "turn": current_turn
+        // Se non ci sono posti liberi si entra come spettatori
+        match table.game.join(&player_id) {
+            Ok(events) => {
+                table.connections.insert(player_id.clone(), tx);
+                advance(&state, &mut table, events);
             }
-        })
-        .to_string();
-        let _ = tx.send(Message::Text(welcome_msg));
-
-        game.broadcast("player_joined", json!({ "id": player_id.clone() }));
+            Err(err) => {
+                eprintln!("Impossibile aggiungere il giocatore: {}", err);
+                let _ = sender.send(Message::Text(error_frame(&err))).await;
+                return;
+            }
+        }
     }
 
     // TASK 1: invia messaggi al client
@@ -76,166 +77,175 @@ async fn handle_socket(socket: WebSocket, state: Arc<Mutex<GameState>>) {
     let pid = player_id.clone();
     let mut recv_task = tokio::spawn(async move {
         while let Some(Ok(Message::Text(text))) = receiver.next().await {
-            if let Ok(json_msg) = serde_json::from_str::<serde_json::Value>(&text) {
+            if let Ok(json_msg) = serde_json::from_str::<Value>(&text) {
                 if let Some(action) = json_msg.get("action").and_then(|a| a.as_str()) {
-                    match action {
-                        "start_game" => {
-                            let mut game = state_clone.lock().await;
-                            game.start_game();
-                            game.broadcast("game_started", json!({}));
-                            broadcast_round_start(&game);
-                        }
-                        "play_card" => {
-                            if let Ok(card) = serde_json::from_value::<Card>(json_msg["card"].clone()) {
-                                let mut game = state_clone.lock().await;
-                                match game.play_card(&pid, card.clone()) {
-                                    Ok(()) => {
-                                        game.broadcast("card_played", json!({ "player_id": pid.clone(), "card": card }));
-                                        send_player_hand(&game, &pid);
-                                        finish_turn_if_ready(&mut game);
-                                    }
-                                    Err(e) => {
-                                        send_to_player(&game, &pid, "error", json!({ "message": e }));
-                                    }
-                                }
-                            }
-                        }
-                        "make_prediction" => {
-                            if let Some(value) = json_msg.get("prediction").and_then(|v| v.as_u64()) {
-                                let mut game = state_clone.lock().await;
-                                match game.make_prediction(&pid, value as u8) {
-                                    Ok(()) => game.broadcast("prediction_made", json!({ "player_id": pid.clone(), "prediction": value })),
-                                    Err(err) => send_to_player(&game, &pid, "error", json!({ "message": err })),
-                                }
-                            }
-                        }
-                        "end_turn" => {
-                            let mut game = state_clone.lock().await;
-                            if game.current_turn_cards.len() == game.players.len() {
-                                finalize_turn(&mut game);
-                            } else {
-                                send_to_player(&game, &pid, "error", json!({ "message": "Turno non ancora completo" }));
-                            }
-                        }
-                        "next_round" => {
-                            let mut game = state_clone.lock().await;
-                            if !game.is_round_over() {
-                                send_to_player(&game, &pid, "error", json!({ "message": "Il round corrente non è terminato" }));
-                            } else if game.round_number >= game.starting_cards {
-                                send_to_player(&game, &pid, "error", json!({ "message": "La partita è già terminata" }));
-                            } else {
-                                game.next_round();
-                                broadcast_round_start(&game);
-                            }
-                        }
-                        _ => {}
+                    let mut table = state_clone.lock().await;
+                    let result = parse_command(action, &json_msg)
+                        .and_then(|command| table.game.handle(&pid, command));
+                    match result {
+                        Ok(events) => advance(&state_clone, &mut table, events),
+                        Err(err) => send_error(&table, &pid, &err),
                     }
                 }
             }
         }
     });
 
-    // Attendi fine di uno dei due task
+    // Attendi fine di uno dei task
     tokio::select! {
         _ = (&mut send_task) => (),
         _ = (&mut recv_task) => (),
     }
+    send_task.abort();
+    recv_task.abort();
 
     // Disconnessione
     {
-        let mut game = state.lock().await;
-        game.remove_player(&player_id);
-        game.broadcast("player_left", json!({ "id": player_id }));
+        let mut table = state.lock().await;
+        table.connections.remove(&player_id);
+        let events = table.game.leave(&player_id);
+        advance(&state, &mut table, events);
     }
+
+    // L'ultimo giocatore uscito chiude il tavolo
+    registry.remove_if_empty(&game_id).await;
 }
 
-//helper per inviare un messaggio a uno specifico giocatore
-fn send_to_player(game: &GameState, player_id: &str, event: &str, data: serde_json::Value) {
-    if let Some(player) = game.players.iter().find(|p| p.id == player_id) {
-        let payload = json!({ "event": event, "data": data }).to_string();
-        let _ = player.sender.send(Message::Text(payload));
-    }
+//traduce un messaggio del client nel comando per il motore
+fn parse_command(action: &str, json_msg: &Value) -> Result<Command, GameError> {
+    let invalid = || GameError::InvalidPayload { action: action.to_string() };
+    let command = match action {
+        "start_game" => Command::StartGame,
+        "make_prediction" => {
+            let prediction = json_msg
+                .get("prediction")
+                .and_then(|v| v.as_u64())
+                .and_then(|v| u8::try_from(v).ok())
+                .ok_or_else(invalid)?;
+            Command::MakePrediction { prediction }
+        }
+        "play_card" => {
+            // Nel round alla cieca si gioca la propria carta senza indicarla
+            let card = serde_json::from_value::<Card>(json_msg["card"].clone()).ok();
+            // Per la matta: "declaration": "high" | "low"
+            let declaration = match json_msg.get("declaration") {
+                Some(value) => Some(
+                    serde_json::from_value::<WildDeclaration>(value.clone()).map_err(|_| invalid())?,
+                ),
+                None => None,
+            };
+            Command::PlayCard { card, declaration }
+        }
+        "contribute_entropy" => {
+            let entropy = json_msg
+                .get("entropy")
+                .and_then(|e| e.as_str())
+                .ok_or_else(invalid)?;
+            Command::ContributeEntropy { entropy: entropy.to_string() }
+        }
+        "legal_cards" => Command::LegalCards,
+        "pause_game" => Command::PauseGame,
+        "resume_game" => Command::ResumeGame,
+        // Solo chi ospita il tavolo: {"difficulty": "easy" | "strong" | "expert"}
+        "add_bot" => {
+            let difficulty = match json_msg.get("difficulty") {
+                Some(value) => serde_json::from_value(value.clone()).map_err(|_| invalid())?,
+                None => BotDifficulty::default(),
+            };
+            Command::AddBot { difficulty }
+        }
+        "set_bot_difficulty" => {
+            let bot_id = json_msg
+                .get("bot_id")
+                .and_then(|b| b.as_str())
+                .ok_or_else(invalid)?;
+            let difficulty = serde_json::from_value(json_msg["difficulty"].clone()).map_err(|_| invalid())?;
+            Command::SetBotDifficulty { bot_id: bot_id.to_string(), difficulty }
+        }
+        _ => return Err(GameError::UnknownAction { action: action.to_string() }),
+    };
+    Ok(command)
 }
 
-//helper per inviare mano aggiornata
-fn send_player_hand(game: &GameState, player_id: &str) {
-    if let Some(player) = game.players.iter().find(|p| p.id == player_id) {
-        let hand = player.hand.clone();
-        let payload = json!({
-            "event": "hand_updated",
-            "data": {
-                "player_id": player.id.clone(),
-                "hand": hand
-            }
-        })
-        .to_string();
-        let _ = player.sender.send(Message::Text(payload));
+//consegna gli eventi e, se tocca a un bot, avvia il task che li fa giocare
+pub fn advance(state: &SharedGame, table: &mut Table, events: Vec<Envelope>) {
+    deliver(table, events);
+    if !table.bots_running && table.game.next_bot().is_some() {
+        table.bots_running = true;
+        tokio::spawn(drive_bots(state.clone()));
     }
 }
 
-
-fn finish_turn_if_ready(game: &mut GameState) {
-    let player_count = game.players.len();
-    if player_count == 0 {
-        return;
-    }
-    if game.current_turn_cards.len() == player_count {
-        finalize_turn(game);
+//fa agire i bot uno alla volta. La scelta (per il bot esperto fino al tempo
+//di riflessione) si fa su una copia della partita senza tenere il tavolo
+//bloccato; il comando passa poi dal motore come quello di un client.
+async fn drive_bots(state: SharedGame) {
+    loop {
+        let (bot_id, game) = {
+            let mut table = state.lock().await;
+            let next = table.game.next_bot().filter(|_| !table.closed && !table.is_abandoned());
+            let Some(bot_id) = next else {
+                table.bots_running = false;
+                return;
+            };
+            (bot_id, table.game.clone())
+        };
+        let seen = game.log.records.len();
+        let id = bot_id.clone();
+        let command = tokio::task::spawn_blocking(move || game.bot_command(&id))
+            .await
+            .ok()
+            .flatten();
+
+        let mut table = state.lock().await;
+        match command.map(|command| table.game.handle(&bot_id, command)) {
+            Some(Ok(events)) => deliver(&table, events),
+            // La partita e andata avanti mentre il bot pensava: si ricalcola
+            _ if table.game.log.records.len() != seen => {}
+            Some(Err(err)) => {
+                eprintln!("Il bot {} non riesce ad agire: {}", bot_id, err);
+                table.bots_running = false;
+                return;
+            }
+            None => {
+                table.bots_running = false;
+                return;
+            }
+        }
     }
 }
 
-fn finalize_turn(game: &mut GameState) {
-    if game.current_turn_cards.is_empty() {
-        return;
-    }
-    if let Some(winner_id) = game.end_turn() {
-        let standings: Vec<serde_json::Value> = game
-            .players
-            .iter()
-            .map(|p| json!({ "player_id": p.id, "tricks_won": p.tricks_won }))
-            .collect();
-        game.broadcast(
-            "turn_ended",
-            json!({
-                "winner_id": winner_id,
-                "standings": standings
-            }),
-        );
-
-        if game.is_round_over() {
-            let results = game.end_round();
-            game.broadcast("round_ended", json!({ "results": results.clone() }));
-
-            if game.round_number >= game.starting_cards {
-                game.broadcast("game_over", json!({ "results": results }));
-            } else {
-                game.next_round();
-                broadcast_round_start(game);
+//consegna gli eventi del motore come frame {event, data}; ogni evento
+//riporta la fase della partita in cui e stato generato
+fn deliver(table: &Table, events: Vec<Envelope>) {
+    for envelope in events {
+        let mut frame = serde_json::to_value(&envelope.event).unwrap_or(Value::Null);
+        if let Some(Value::Object(data)) = frame.get_mut("data") {
+            data.insert("phase".to_string(), json!(envelope.phase));
+        }
+        let payload = frame.to_string();
+        match &envelope.audience {
+            Audience::Everyone => {
+                for connection in table.connections.values() {
+                    let _ = connection.send(Message::Text(payload.clone()));
+                }
+            }
+            Audience::Player(player_id) => {
+                if let Some(connection) = table.connections.get(player_id) {
+                    let _ = connection.send(Message::Text(payload));
+                }
             }
         }
     }
 }
 
-//notifica inizio di un round
-fn broadcast_round_start(game: &GameState) {
-    game.broadcast(
-        "round_started",
-        json!({
-            "round_number": game.round_number,
-            "starting_cards": game.starting_cards
-        }),
-    );
-
-    for player in &game.players {
-        let hand = player.hand.clone();
-        let payload = json!({
-            "event": "hand_updated",
-            "data": {
-                "player_id": player.id.clone(),
-                "hand": hand
-            }
-        })
-        .to_string();
-        let _ = player.sender.send(Message::Text(payload));
+//helper per inviare un errore strutturato {code, message, details}
+fn send_error(table: &Table, player_id: &str, err: &GameError) {
+    if let Some(connection) = table.connections.get(player_id) {
+        let _ = connection.send(Message::Text(error_frame(err)));
     }
 }
+
+fn error_frame(err: &GameError) -> String {
+    json!({ "event": "error", "data": err.to_json() }).to_string()
+}